@@ -1,4 +1,4 @@
-use pixel_canvas::{input::MouseState, prelude::*};
+use pixel_canvas::prelude::*;
 
 fn main() {
     let mut t = 0;
@@ -0,0 +1,29 @@
+//! Compare `Image::fill` against filling the image one pixel at a time.
+//!
+//! Run with `cargo run --release --example fill_benchmark`.
+
+use pixel_canvas::prelude::*;
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 200;
+
+fn time(image: &mut Image, mut fill: impl FnMut(&mut Image, Color)) -> Duration {
+    let start = Instant::now();
+    for i in 0..ITERATIONS {
+        fill(image, Color::gray(i as u8));
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let mut image = Image::new(1920, 1080);
+    let naive = time(&mut image, |image, color| {
+        for pix in image.iter_mut() {
+            *pix = color;
+        }
+    });
+    let fill = time(&mut image, Image::fill);
+    println!("1920x1080, average of {} fills", ITERATIONS);
+    println!("naive loop:  {:.2?}", naive);
+    println!("Image::fill: {:.2?}", fill);
+}
@@ -11,8 +11,7 @@ fn main() {
     // The canvas will render for you at up to 60fps.
     canvas.render(|mouse, image| {
         // Modify the `image` based on your state.
-        let width = image.width() as usize;
-        for (y, row) in image.chunks_mut(width).enumerate() {
+        for (y, row) in image.rows_mut_enumerated() {
             for (x, pixel) in row.iter_mut().enumerate() {
                 let dx = x as i32 - mouse.x;
                 let dy = y as i32 - mouse.y;
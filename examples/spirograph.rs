@@ -1,4 +1,4 @@
-use pixel_canvas::{input::MouseState, prelude::*};
+use pixel_canvas::prelude::*;
 
 fn spirograph(l: f32, k: f32, t: f32) -> (f32, f32) {
     const R: f32 = 0.9;
@@ -23,9 +23,9 @@ fn main() {
         let k = (mouse.y / 15 * 15) as f32 / image.height() as f32;
         for t in 0..100000 {
             let (x, y) = spirograph(l, k, t as f32 / 100.0);
-            let x = (x * aspect / 2.0) as usize + image.width() / 2;
-            let y = (y * aspect / 2.0) as usize + image.height() / 2;
-            image[XY(x, y)] = Color::rgb(127, 255, 0);
+            let x = x * aspect / 2.0 + image.width() as f32 / 2.0;
+            let y = y * aspect / 2.0 + image.height() as f32 / 2.0;
+            image.plot_aa(x, y, Color::rgb(127, 255, 0));
         }
     });
 }
@@ -0,0 +1,43 @@
+//! Compare the frame time of uploading through a pixel buffer object against
+//! writing the texture directly.
+//!
+//! Run with `cargo run --release --example upload_benchmark`, and again with
+//! `-- --no-pixel-buffer` to see the difference.
+
+use pixel_canvas::prelude::*;
+
+const FRAMES: usize = 600;
+
+fn main() {
+    let pixel_buffer = !std::env::args().any(|arg| arg == "--no-pixel-buffer");
+    let canvas = Canvas::new(1280, 720)
+        .title(if pixel_buffer {
+            "Upload Benchmark (pixel buffer)"
+        } else {
+            "Upload Benchmark (direct)"
+        })
+        .hidpi(true)
+        .show_ms(true)
+        .max_frames(FRAMES)
+        .pixel_buffer(pixel_buffer);
+    canvas.render_with_info(|info, _state, image| {
+        // Change every pixel, so the whole image has to be uploaded.
+        let t = info.stats.frames as usize;
+        for (y, row) in image.rows_mut_enumerated() {
+            for (x, pix) in row.iter_mut().enumerate() {
+                *pix = Color {
+                    r: (x + t) as u8,
+                    g: (y + t) as u8,
+                    b: (x ^ y) as u8,
+                };
+            }
+        }
+        info.set_status(format!("average {:.2?}", info.stats.average));
+        if info.stats.frames + 1 == FRAMES as u64 {
+            println!(
+                "{} frames, average frame time {:.2?}",
+                FRAMES, info.stats.average
+            );
+        }
+    });
+}
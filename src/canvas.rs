@@ -36,8 +36,7 @@
 //! # }
 //! # let canvas = Canvas::new(512, 512).state(MouseState::new());
 //! canvas.render(|mouse, image| {
-//!     let width = image.width() as usize;
-//!     for (y, row) in image.chunks_mut(width).enumerate() {
+//!     for (y, row) in image.rows_mut_enumerated() {
 //!         for (x, pixel) in row.iter_mut().enumerate() {
 //!             *pixel = make_your_own_color(x, y, mouse.x, mouse.y);
 //!         }
@@ -45,21 +44,91 @@
 //! });
 //! ```
 
-use crate::image::Image;
+use crate::{
+    color::Color,
+    image::{Image, Pixel},
+    math::Rng,
+};
 use glium::{
+    backend::glutin::DisplayCreationError,
     glutin::{
         self,
-        event::{Event, StartCause},
-        event_loop::ControlFlow,
+        event::{Event, StartCause, WindowEvent},
+        event_loop::{ControlFlow, EventLoop, EventLoopClosed, EventLoopProxy},
+        window::{Icon, WindowBuilder},
+        GlProfile, GlRequest,
     },
-    Rect, Surface,
+    texture::pixel_buffer::PixelBuffer,
+    BlitTarget, Rect, Surface, SwapBuffersError,
+};
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fmt,
+    io::{self, Write},
+    sync::Mutex,
+    time::{Duration, Instant},
 };
-use std::time::{Duration, Instant};
+
+/// The time between frames when rendering at a fixed framerate.
+const FRAME_BUDGET: Duration = Duration::from_nanos(16_666_667);
 
 /// A type that represents an event handler.
 ///
 /// It returns true if the state is changed.
-pub type EventHandler<State> = fn(&CanvasInfo, &mut State, &Event<()>) -> bool;
+pub type EventHandler<State, UserEvent = ()> =
+    fn(&CanvasInfo, &mut State, &Event<UserEvent>) -> bool;
+
+/// What the canvas should do after an event handler has run.
+///
+/// Event handlers can return anything that converts into a
+/// `HandlerResponse`, so handlers that return `bool` keep working: `true`
+/// means [`Redraw`] and `false` means [`Ignore`].
+///
+/// [`Redraw`]: enum.HandlerResponse.html#variant.Redraw
+/// [`Ignore`]: enum.HandlerResponse.html#variant.Ignore
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HandlerResponse {
+    /// Nothing changed, there's no need to render a new frame.
+    Ignore,
+    /// The state changed, so a new frame should be rendered.
+    Redraw,
+    /// Close the window and exit.
+    Exit,
+    /// Stop calling the render callback, leaving the last frame on screen.
+    /// Events are still passed to the event handler while paused.
+    Pause,
+    /// Resume rendering after a [`Pause`](#variant.Pause).
+    Resume,
+}
+
+impl From<bool> for HandlerResponse {
+    fn from(changed: bool) -> HandlerResponse {
+        if changed {
+            HandlerResponse::Redraw
+        } else {
+            HandlerResponse::Ignore
+        }
+    }
+}
+
+/// How the image is filtered when it's scaled to fit the window.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ScalingFilter {
+    /// Use the nearest pixel, which keeps pixel art crisp.
+    Nearest,
+    /// Blend between neighboring pixels, which looks smoother.
+    Linear,
+}
+
+impl ScalingFilter {
+    fn magnify_filter(self) -> glium::uniforms::MagnifySamplerFilter {
+        match self {
+            ScalingFilter::Nearest => glium::uniforms::MagnifySamplerFilter::Nearest,
+            ScalingFilter::Linear => glium::uniforms::MagnifySamplerFilter::Linear,
+        }
+    }
+}
 
 /// Information about the [`Canvas`](struct.Canvas.html).
 pub struct CanvasInfo {
@@ -71,62 +140,618 @@ pub struct CanvasInfo {
     pub title: String,
     /// Whether the canvas will render in hidpi mode. Defaults to `false`.
     pub hidpi: bool,
+    /// The DPI factor to use instead of the monitor's, whether or not hidpi
+    /// is enabled. Defaults to `None`, which uses the monitor's.
+    pub dpi_override: Option<f64>,
     /// The DPI factor. If hidpi is on, the virtual dimensions are multiplied
     /// by this factor to create the actual image resolution. For example, if
     /// you're on a Retina Macbook, this will be 2.0, so the image will be
     /// twice the resolution that you specified.
+    ///
+    /// If the window moves to a monitor with a different scale factor, this
+    /// is updated and the image is reallocated at the new resolution. The
+    /// `ScaleFactorChanged` event is still passed to your event handler, so
+    /// you can invalidate anything that depends on the resolution.
     pub dpi: f64,
+    /// The scale factor of the monitor the window is on, whether or not
+    /// hidpi is enabled. This converts the physical pixels of the window into
+    /// the virtual pixels that the canvas dimensions are measured in.
+    pub scale_factor: f64,
     /// Whether the window title will display the time to render a frame.
     /// Defaults to `false`.
     pub show_ms: bool,
     /// Only call the render callback if there's a state change.
     /// Defaults to `false`, which means it will instead render at a fixed framerate.
     pub render_on_change: bool,
+    /// The color the image is cleared to before each frame is rendered.
+    /// Defaults to `None`, which leaves the previous frame in the image.
+    pub clear_color: Option<Color>,
+    /// Whether rendering is paused. While paused, the render callback isn't
+    /// called, but events are still handled. Defaults to `false`.
+    pub paused: bool,
+    /// Whether the system cursor is hidden while over the window.
+    /// Defaults to `false`.
+    pub hide_cursor: bool,
+    /// Whether the cursor is grabbed, preventing it from leaving the window.
+    /// Defaults to `false`.
+    pub grab_cursor: bool,
+    /// The filter used when the image is scaled to fit the window.
+    /// Defaults to [`ScalingFilter::Linear`](enum.ScalingFilter.html#variant.Linear).
+    pub scaling_filter: ScalingFilter,
+    /// Whether the image keeps its aspect ratio when it doesn't match the
+    /// window's, leaving black bars around it instead of stretching.
+    /// Defaults to `false`.
+    pub preserve_aspect: bool,
+    /// The resolution of the image, if it's independent of the window size.
+    /// Defaults to `None`, which makes the image the size of the window.
+    pub render_size: Option<(usize, usize)>,
+    /// How many times larger than the displayed image the image given to
+    /// the render callback is, in each dimension. Defaults to `1`.
+    pub supersample: u32,
+    /// The number of samples per pixel requested for the window's
+    /// framebuffer. Defaults to `0`, which doesn't multisample.
+    pub multisampling: u16,
+    /// The OpenGL version requested for the context. Defaults to
+    /// `GlRequest::Latest`.
+    pub gl_request: GlRequest,
+    /// The OpenGL profile requested for the context. Defaults to `None`,
+    /// which lets the platform choose.
+    pub gl_profile: Option<GlProfile>,
+    /// Whether the window can be resized. When it's resized, the canvas
+    /// dimensions are updated and the image is reallocated to match.
+    /// Defaults to `false`.
+    pub resizable: bool,
+    /// Whether resizing the window keeps its aspect ratio. Defaults to
+    /// `false`.
+    pub aspect_ratio_lock: bool,
+    /// The number of frames to render before closing the window.
+    /// Defaults to `None`, which renders until the window is closed.
+    pub max_frames: Option<usize>,
+    /// The seed for the random number generator given to
+    /// [`render_with_rng`](struct.Canvas.html#method.render_with_rng).
+    /// Defaults to `0`.
+    pub seed: u64,
+    /// Whether frames are uploaded to the GPU through a pixel buffer
+    /// object. Defaults to `true`.
+    pub pixel_buffer: bool,
+    /// Whether each frame is written to stdout as raw bytes. Defaults to
+    /// `false`.
+    pub pipe_raw: bool,
+    /// Whether the window is shown. Defaults to `true`.
+    pub visible: bool,
+    /// Timing statistics for the frames rendered so far.
+    pub stats: FrameStats,
+    status: Option<String>,
+    /// Whether the window title needs to be rebuilt.
+    title_stale: bool,
+    changes: Mutex<InfoChanges>,
+}
+
+/// Settings changed while the canvas is running, to apply after the current
+/// callback returns.
+#[derive(Default)]
+struct InfoChanges {
+    status: Option<Option<String>>,
+    show_ms: Option<bool>,
+    clear_color: Option<Option<Color>>,
+    render_on_change: Option<bool>,
+    visible: Option<bool>,
+}
+
+impl CanvasInfo {
+    /// Create canvas information with the default settings.
+    ///
+    /// The canvas creates this for you, but it's useful for testing your
+    /// event handlers.
+    pub fn new(width: usize, height: usize) -> CanvasInfo {
+        CanvasInfo {
+            width,
+            height,
+            hidpi: false,
+            dpi_override: None,
+            dpi: 1.0,
+            scale_factor: 1.0,
+            title: "Canvas".into(),
+            show_ms: false,
+            render_on_change: false,
+            clear_color: None,
+            paused: false,
+            hide_cursor: false,
+            grab_cursor: false,
+            scaling_filter: ScalingFilter::Linear,
+            preserve_aspect: false,
+            render_size: None,
+            supersample: 1,
+            multisampling: 0,
+            gl_request: GlRequest::Latest,
+            gl_profile: None,
+            resizable: false,
+            aspect_ratio_lock: false,
+            max_frames: None,
+            seed: 0,
+            pixel_buffer: true,
+            pipe_raw: false,
+            visible: true,
+            stats: FrameStats::default(),
+            status: None,
+            title_stale: true,
+            changes: Mutex::new(InfoChanges::default()),
+        }
+    }
+
+    /// Show some status text in the window title, after the base title.
+    ///
+    /// This can be called from the render callback or an event handler, and
+    /// is a cheap way to display live values without drawing any text. The
+    /// title is updated after the callback returns.
+    /// ```rust,no_run
+    /// # use pixel_canvas::prelude::*;
+    /// let mut frame = 0;
+    /// Canvas::new(512, 512).render_with_info(move |info, _, _| {
+    ///     frame += 1;
+    ///     info.set_status(format!("frame {}", frame));
+    /// });
+    /// ```
+    pub fn set_status(&self, status: impl Into<String>) {
+        self.changes.lock().unwrap().status = Some(Some(status.into()));
+    }
+
+    /// Remove the status text from the window title.
+    pub fn clear_status(&self) {
+        self.changes.lock().unwrap().status = Some(None);
+    }
+
+    /// Change whether the frame time is shown while the canvas is running.
+    ///
+    /// Event handlers and render callbacks only get shared access to the
+    /// canvas information, so the settings that are safe to change while
+    /// running have setters like this one, which take effect after the
+    /// callback returns. The other settings control how the window and
+    /// image are created, and changing them while running isn't supported.
+    /// ```rust,no_run
+    /// # use pixel_canvas::{prelude::*, input::{ElementState, KeyboardInput, VirtualKeyCode}};
+    /// Canvas::new(512, 512)
+    ///     .input(|info, _, event| {
+    ///         if let Event::WindowEvent {
+    ///             event:
+    ///                 WindowEvent::KeyboardInput {
+    ///                     input:
+    ///                         KeyboardInput {
+    ///                             state: ElementState::Pressed,
+    ///                             virtual_keycode: Some(VirtualKeyCode::D),
+    ///                             ..
+    ///                         },
+    ///                     ..
+    ///                 },
+    ///             ..
+    ///         } = event
+    ///         {
+    ///             info.set_show_ms(!info.show_ms);
+    ///         }
+    ///         false
+    ///     })
+    ///     .render(|_, image| {
+    ///         // ...
+    ///     });
+    /// ```
+    pub fn set_show_ms(&self, enabled: bool) {
+        self.changes.lock().unwrap().show_ms = Some(enabled);
+    }
+
+    /// Change the clear color while the canvas is running.
+    ///
+    /// See [`set_show_ms`](#method.set_show_ms) for when this takes effect.
+    pub fn set_clear_color(&self, color: Option<Color>) {
+        self.changes.lock().unwrap().clear_color = Some(color);
+    }
+
+    /// Change whether the canvas only renders on changes while it's running.
+    ///
+    /// See [`set_show_ms`](#method.set_show_ms) for when this takes effect.
+    pub fn set_render_on_change(&self, enabled: bool) {
+        self.changes.lock().unwrap().render_on_change = Some(enabled);
+    }
+
+    /// Show or hide the window while the canvas is running.
+    ///
+    /// This lets you start the canvas hidden with
+    /// [`Canvas::visible`](struct.Canvas.html#method.visible), and show it
+    /// once your sketch is ready. See [`set_show_ms`](#method.set_show_ms)
+    /// for when this takes effect.
+    pub fn set_visible(&self, visible: bool) {
+        self.changes.lock().unwrap().visible = Some(visible);
+    }
+
+    /// Apply the settings changed while running, returning whether any of
+    /// them need a redraw.
+    fn apply_changes(&mut self) -> bool {
+        let changes = std::mem::take(&mut *self.changes.lock().unwrap());
+        let mut changed = false;
+        if let Some(status) = changes.status {
+            // The status only shows in the title, so it doesn't need a redraw.
+            self.status = status;
+            self.title_stale = true;
+        }
+        if let Some(show_ms) = changes.show_ms {
+            self.show_ms = show_ms;
+            self.title_stale = true;
+            changed = true;
+        }
+        if let Some(clear_color) = changes.clear_color {
+            self.clear_color = clear_color;
+            changed = true;
+        }
+        if let Some(render_on_change) = changes.render_on_change {
+            self.render_on_change = render_on_change;
+            changed = true;
+        }
+        if let Some(visible) = changes.visible {
+            self.visible = visible;
+            changed = true;
+        }
+        changed
+    }
+
+    /// Update the scale factor, and the DPI that depends on it.
+    fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.scale_factor = scale_factor;
+        self.dpi = match self.dpi_override {
+            Some(dpi) => dpi,
+            None if self.hidpi => scale_factor,
+            None => 1.0,
+        };
+    }
+
+    /// The size of the displayed image, before supersampling.
+    pub(crate) fn image_size(&self) -> (usize, usize) {
+        self.render_size.unwrap_or((
+            (self.width as f64 * self.dpi) as usize,
+            (self.height as f64 * self.dpi) as usize,
+        ))
+    }
+
+    /// The full window title, including the status and frame time.
+    fn window_title(&self) -> String {
+        let mut title = self.title.clone();
+        if let Some(status) = &self.status {
+            title.push_str(" - ");
+            title.push_str(status);
+        }
+        if self.show_ms && self.stats.frames > 0 {
+            title.push_str(&format!(" - {:3}ms", self.stats.last.as_millis()));
+        }
+        title
+    }
+}
+
+/// Timing statistics for rendered frames, available as
+/// [`CanvasInfo::stats`](struct.CanvasInfo.html#structfield.stats).
+///
+/// A frame's time covers calling the render callback, uploading the image,
+/// and drawing it into the window. It stops before swapping the buffers,
+/// which waits for the screen's refresh with vsync, so frames that keep up
+/// with the refresh rate aren't counted as dropped.
+/// ```rust,no_run
+/// # use pixel_canvas::prelude::*;
+/// Canvas::new(512, 512).render_with_info(|info, _, _| {
+///     if info.stats.dropped > 0 {
+///         info.set_status(format!("{} dropped frames", info.stats.dropped));
+///     }
+/// });
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FrameStats {
+    /// How long the last frame took.
+    pub last: Duration,
+    /// The average time of the last [`WINDOW`](#associatedconstant.WINDOW)
+    /// frames.
+    pub average: Duration,
+    /// The total number of frames rendered.
+    pub frames: u64,
+    /// The number of frames that took longer than 1/60th of a second.
+    pub dropped: u64,
+    history: VecDeque<Duration>,
+}
+
+impl FrameStats {
+    /// The number of frames that the rolling average is taken over.
+    pub const WINDOW: usize = 60;
+
+    /// Record the time of a new frame.
+    fn record(&mut self, time: Duration) {
+        if self.history.len() == Self::WINDOW {
+            self.history.pop_front();
+        }
+        self.history.push_back(time);
+        self.last = time;
+        self.average = self.history.iter().sum::<Duration>() / self.history.len() as u32;
+        self.frames += 1;
+        if time > FRAME_BUDGET {
+            self.dropped += 1;
+        }
+    }
 }
 
 /// A [`Canvas`](struct.Canvas.html) manages a window and event loop, handing
 /// the current state to the renderer, and presenting its image on the screen.
-pub struct Canvas<State, Handler = EventHandler<State>> {
+///
+/// The image is RGB by default, see
+/// [`pixel_format`](struct.Canvas.html#method.pixel_format) to use another
+/// format.
+pub struct Canvas<State, Handler = EventHandler<State>, Format = Color, UserEvent: 'static = ()> {
     info: CanvasInfo,
-    image: Image,
+    window: WindowBuilder,
+    image: Image<Format>,
     state: State,
     event_handler: Handler,
+    event_loop: Option<EventLoop<CanvasEvent<UserEvent>>>,
+    on_close: Option<CloseCallback<State>>,
+    on_resize: Option<ResizeCallback<State>>,
+    on_tick: Option<TickCallback<State>>,
+    clear_with: Option<ClearCallback<State, Format>>,
+}
+
+/// The callback given to [`Canvas::on_close`](struct.Canvas.html#method.on_close).
+type CloseCallback<State> = Box<dyn FnOnce(&mut State)>;
+
+/// The callback given to [`Canvas::on_resize`](struct.Canvas.html#method.on_resize).
+type ResizeCallback<State> = Box<dyn FnMut(&mut State, usize, usize)>;
+
+/// The callback given to [`Canvas::on_tick`](struct.Canvas.html#method.on_tick).
+type TickCallback<State> = Box<dyn FnMut(&mut State) -> bool>;
+
+/// The callback given to [`Canvas::clear_with`](struct.Canvas.html#method.clear_with).
+type ClearCallback<State, Format> = Box<dyn FnMut(&mut Image<Format>, &State)>;
+
+/// When the event loop renders frames.
+enum Schedule {
+    /// Render at a fixed framerate, or on changes with `render_on_change`.
+    Fixed,
+    /// Call the render callback continuously, and present the frames it
+    /// returns `true` for.
+    Manual,
+}
+
+/// The events sent to the event loop through a proxy.
+enum CanvasEvent<UserEvent> {
+    Redraw,
+    User(UserEvent),
+}
+
+/// A handle to request that the canvas renders a new frame, even from another
+/// thread.
+///
+/// This is useful with [`render_on_change`], when the state changes outside
+/// of an event handler, for example when a background thread finishes
+/// computing something. Get one with [`Canvas::redraw_requester`].
+/// ```rust,no_run
+/// # use pixel_canvas::prelude::*;
+/// let mut canvas = Canvas::new(512, 512).render_on_change(true);
+/// let redraw = canvas.redraw_requester();
+/// std::thread::spawn(move || {
+///     // Compute something slow...
+///     redraw.request();
+/// });
+/// canvas.render(|_, image| {
+///     // ...
+/// });
+/// ```
+///
+/// [`render_on_change`]: struct.Canvas.html#method.render_on_change
+/// [`Canvas::redraw_requester`]: struct.Canvas.html#method.redraw_requester
+pub struct RedrawRequester<UserEvent: 'static = ()> {
+    proxy: EventLoopProxy<CanvasEvent<UserEvent>>,
+}
+
+impl<UserEvent: 'static> RedrawRequester<UserEvent> {
+    /// Wake up the canvas and render a new frame.
+    ///
+    /// This does nothing if the canvas has already closed.
+    pub fn request(&self) {
+        let _ = self.proxy.send_event(CanvasEvent::Redraw);
+    }
+}
+
+impl<UserEvent: 'static> Clone for RedrawRequester<UserEvent> {
+    fn clone(&self) -> Self {
+        RedrawRequester {
+            proxy: self.proxy.clone(),
+        }
+    }
+}
+
+/// A handle to send your own events to the canvas's event handler, even from
+/// another thread.
+///
+/// Set the type of event with [`Canvas::user_event`], and get a proxy with
+/// [`Canvas::event_proxy`]. Events arrive at your event handler as
+/// `Event::UserEvent`.
+/// ```rust,no_run
+/// # use pixel_canvas::prelude::*;
+/// enum Message {
+///     Done(Vec<Color>),
+/// }
+///
+/// let mut canvas = Canvas::new(512, 512)
+///     .user_event::<Message>()
+///     .state(Vec::new())
+///     .input(|_, colors, event| match event {
+///         Event::UserEvent(Message::Done(result)) => {
+///             *colors = result.clone();
+///             true
+///         }
+///         _ => false,
+///     });
+/// let proxy = canvas.event_proxy();
+/// std::thread::spawn(move || {
+///     // Run a slow simulation...
+///     let _ = proxy.send_event(Message::Done(vec![Color::WHITE]));
+/// });
+/// canvas.render(|colors, image| {
+///     // ...
+/// });
+/// ```
+///
+/// [`Canvas::user_event`]: struct.Canvas.html#method.user_event
+/// [`Canvas::event_proxy`]: struct.Canvas.html#method.event_proxy
+pub struct EventProxy<UserEvent: 'static> {
+    proxy: EventLoopProxy<CanvasEvent<UserEvent>>,
+}
+
+impl<UserEvent: 'static> EventProxy<UserEvent> {
+    /// Send an event to the canvas, waking it up.
+    ///
+    /// If the canvas has already closed, the event is given back in the
+    /// error.
+    pub fn send_event(&self, event: UserEvent) -> Result<(), EventLoopClosed<UserEvent>> {
+        self.proxy.send_event(CanvasEvent::User(event)).map_err(
+            |EventLoopClosed(event)| match event {
+                CanvasEvent::User(event) => EventLoopClosed(event),
+                CanvasEvent::Redraw => unreachable!(),
+            },
+        )
+    }
+}
+
+impl<UserEvent: 'static> Clone for EventProxy<UserEvent> {
+    fn clone(&self) -> Self {
+        EventProxy {
+            proxy: self.proxy.clone(),
+        }
+    }
+}
+
+/// An error that stops a canvas from rendering.
+#[derive(Debug)]
+pub enum CanvasError {
+    /// The window or its OpenGL context couldn't be created, for example
+    /// because the system has no OpenGL support.
+    Display(DisplayCreationError),
+    /// A frame couldn't be drawn, for example because the OpenGL context was
+    /// lost.
+    Draw(SwapBuffersError),
+}
+
+impl fmt::Display for CanvasError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CanvasError::Display(err) => write!(f, "couldn't create the window: {}", err),
+            CanvasError::Draw(err) => write!(f, "couldn't draw a frame: {}", err),
+        }
+    }
+}
+
+impl Error for CanvasError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CanvasError::Display(err) => Some(err),
+            CanvasError::Draw(err) => Some(err),
+        }
+    }
 }
 
 impl Canvas<()> {
     /// Create a new canvas with a given virtual window dimensions.
     pub fn new(width: usize, height: usize) -> Canvas<()> {
         Canvas {
-            info: CanvasInfo {
-                width,
-                height,
-                hidpi: false,
-                dpi: 1.0,
-                title: "Canvas".into(),
-                show_ms: false,
-                render_on_change: false,
-            },
+            info: CanvasInfo::new(width, height),
+            window: WindowBuilder::new().with_resizable(false),
             image: Image::new(width, height),
             state: (),
             event_handler: |_, (), _| false,
+            event_loop: None,
+            on_close: None,
+            on_resize: None,
+            on_tick: None,
+            clear_with: None,
         }
     }
 }
 
-impl<State, Handler> Canvas<State, Handler>
+impl<State, Handler, Response, Format, UserEvent> Canvas<State, Handler, Format, UserEvent>
 where
-    Handler: FnMut(&CanvasInfo, &mut State, &Event<()>) -> bool + 'static,
+    Handler: FnMut(&CanvasInfo, &mut State, &Event<UserEvent>) -> Response + 'static,
+    Response: Into<HandlerResponse>,
     State: 'static,
+    Format: Pixel,
+    UserEvent: 'static,
 {
     /// Set the attached state.
     ///
-    /// Attaching a new state object will reset the input handler.
-    pub fn state<NewState>(self, state: NewState) -> Canvas<NewState, EventHandler<NewState>> {
+    /// Attaching a new state object will reset the input handler, and the
+    /// [`on_close`](struct.Canvas.html#method.on_close),
+    /// [`on_resize`](struct.Canvas.html#method.on_resize),
+    /// [`on_tick`](struct.Canvas.html#method.on_tick), and
+    /// [`clear_with`](struct.Canvas.html#method.clear_with) callbacks, since
+    /// they take the old state. Call `state` before
+    /// [`input`](struct.Canvas.html#method.input), not after, or use
+    /// [`with_state_and_input`](struct.Canvas.html#method.with_state_and_input)
+    /// to set both at once.
+    pub fn state<NewState>(
+        self,
+        state: NewState,
+    ) -> Canvas<NewState, EventHandler<NewState, UserEvent>, Format, UserEvent> {
         Canvas {
             info: self.info,
+            window: self.window,
             image: self.image,
             state,
             event_handler: |_, _, _| false,
+            event_loop: self.event_loop,
+            on_close: None,
+            on_resize: None,
+            on_tick: None,
+            clear_with: None,
+        }
+    }
+
+    /// Set the attached state and the input handler for it at the same time.
+    ///
+    /// This is the same as calling [`state`](struct.Canvas.html#method.state)
+    /// and then [`input`](struct.Canvas.html#method.input), but can't be
+    /// accidentally written in the wrong order.
+    /// ```rust,no_run
+    /// # use pixel_canvas::prelude::*;
+    /// Canvas::new(512, 512)
+    ///     .with_state_and_input(MouseState::new(), MouseState::handle_input)
+    ///     .render(|mouse, image| {
+    ///         // ...
+    ///     });
+    /// ```
+    ///
+    /// Written in the wrong order, the handler is attached while the state is
+    /// still `()`, so it doesn't type-check:
+    /// ```compile_fail
+    /// # use pixel_canvas::prelude::*;
+    /// Canvas::new(512, 512)
+    ///     .input(MouseState::handle_input)
+    ///     .state(MouseState::new())
+    ///     .render(|mouse, image| {
+    ///         // ...
+    ///     });
+    /// ```
+    /// A handler that fits the old state, like a closure that ignores it,
+    /// compiles but is silently dropped by `state`.
+    pub fn with_state_and_input<NewState, NewHandler, NewResponse>(
+        self,
+        state: NewState,
+        handler: NewHandler,
+    ) -> Canvas<NewState, NewHandler, Format, UserEvent>
+    where
+        NewHandler: FnMut(&CanvasInfo, &mut NewState, &Event<UserEvent>) -> NewResponse + 'static,
+        NewResponse: Into<HandlerResponse>,
+    {
+        Canvas {
+            info: self.info,
+            window: self.window,
+            image: self.image,
+            state,
+            event_handler: handler,
+            event_loop: self.event_loop,
+            on_close: None,
+            on_resize: None,
+            on_tick: None,
+            clear_with: None,
         }
     }
 
@@ -156,6 +781,30 @@ where
         }
     }
 
+    /// Use a fixed DPI factor, instead of the one the monitor reports.
+    ///
+    /// This overrides [`hidpi`](struct.Canvas.html#method.hidpi)'s automatic
+    /// detection, so the image is always the canvas dimensions multiplied by
+    /// `factor`, even when the window moves between monitors. It's useful
+    /// for displays that report the wrong scale, and for testing how a
+    /// sketch handles hidpi on a monitor that isn't.
+    /// ```rust,no_run
+    /// # use pixel_canvas::prelude::*;
+    /// Canvas::new(320, 240).dpi_override(2.0).render_with_info(|info, _, image| {
+    ///     assert_eq!(info.dpi, 2.0);
+    ///     assert_eq!(image.width(), 640);
+    /// });
+    /// ```
+    pub fn dpi_override(self, factor: f64) -> Self {
+        Self {
+            info: CanvasInfo {
+                dpi_override: Some(factor),
+                ..self.info
+            },
+            ..self
+        }
+    }
+
     /// Whether to show a frame duration in the title bar.
     ///
     /// Defaults to `false`.
@@ -182,20 +831,718 @@ where
         }
     }
 
+    /// Clear the image to a color before each frame is rendered.
+    ///
+    /// By default the image isn't cleared, so it still contains the previous
+    /// frame when your render callback is called. Clearing only happens right
+    /// before your render callback is called, so with
+    /// [`render_on_change`](struct.Canvas.html#method.render_on_change) the
+    /// image is only cleared when the state changes.
+    pub fn clear_color(self, color: Color) -> Self {
+        Self {
+            info: CanvasInfo {
+                clear_color: Some(color),
+                ..self.info
+            },
+            ..self
+        }
+    }
+
+    /// Provide a callback that draws the background before each frame is
+    /// rendered.
+    ///
+    /// This is like [`clear_color`](struct.Canvas.html#method.clear_color),
+    /// but can fill the image with anything, and change it from frame to
+    /// frame, keeping the background separate from your render callback. It's
+    /// called right after the clear color is applied, if there is one. Since
+    /// it takes the state and the image, set it after
+    /// [`state`](struct.Canvas.html#method.state) and
+    /// [`pixel_format`](struct.Canvas.html#method.pixel_format), which reset
+    /// it.
+    /// ```rust,no_run
+    /// # use pixel_canvas::prelude::*;
+    /// Canvas::new(512, 512)
+    ///     .state(0u8)
+    ///     .clear_with(|image, &hue| {
+    ///         image.fill_vertical_gradient(Color::rgb(hue, 0, 64), Color::BLACK);
+    ///     })
+    ///     .render(|hue, image| {
+    ///         *hue = hue.wrapping_add(1);
+    ///         // ...
+    ///     });
+    /// ```
+    pub fn clear_with(self, callback: impl FnMut(&mut Image<Format>, &State) + 'static) -> Self {
+        Self {
+            clear_with: Some(Box::new(callback)),
+            ..self
+        }
+    }
+
+    /// Whether to start with rendering paused.
+    ///
+    /// Defaults to `false`.
+    /// Unlike [`render_on_change`], rendering stays paused until an event
+    /// handler returns [`HandlerResponse::Resume`], even if the state
+    /// changes. Note that when starting paused, nothing is rendered until
+    /// then.
+    ///
+    /// [`render_on_change`]: struct.Canvas.html#method.render_on_change
+    /// [`HandlerResponse::Resume`]: enum.HandlerResponse.html#variant.Resume
+    pub fn paused(self, enabled: bool) -> Self {
+        Self {
+            info: CanvasInfo {
+                paused: enabled,
+                ..self.info
+            },
+            ..self
+        }
+    }
+
+    /// Whether to hide the system cursor while it's over the window.
+    ///
+    /// Defaults to `false`.
+    pub fn hide_cursor(self, enabled: bool) -> Self {
+        Self {
+            info: CanvasInfo {
+                hide_cursor: enabled,
+                ..self.info
+            },
+            ..self
+        }
+    }
+
+    /// Whether to grab the cursor, preventing it from leaving the window.
+    ///
+    /// Defaults to `false`.
+    /// This is useful for camera controls, where you want to keep turning
+    /// even once the cursor would have hit the edge of the screen. The cursor
+    /// position stops being useful while grabbed, so use the relative motion
+    /// tracked by [`MouseState`] instead. Grabbing doesn't hide
+    /// the cursor, combine it with [`hide_cursor`] for that.
+    ///
+    /// Platform caveats: on macOS the cursor is locked in place rather than
+    /// confined to the window, and some platforms (like iOS, Android, and
+    /// the web) don't support grabbing at all. If grabbing fails, the canvas
+    /// prints a warning and continues without it.
+    ///
+    /// [`hide_cursor`]: struct.Canvas.html#method.hide_cursor
+    /// [`MouseState`]: ../input/struct.MouseState.html
+    pub fn grab_cursor(self, enabled: bool) -> Self {
+        Self {
+            info: CanvasInfo {
+                grab_cursor: enabled,
+                ..self.info
+            },
+            ..self
+        }
+    }
+
+    /// Set the filter used when the image is scaled to fit the window.
+    ///
+    /// Defaults to [`ScalingFilter::Linear`]. Use [`ScalingFilter::Nearest`]
+    /// to keep low resolution pixel art crisp.
+    ///
+    /// [`ScalingFilter::Linear`]: enum.ScalingFilter.html#variant.Linear
+    /// [`ScalingFilter::Nearest`]: enum.ScalingFilter.html#variant.Nearest
+    pub fn scaling_filter(self, filter: ScalingFilter) -> Self {
+        Self {
+            info: CanvasInfo {
+                scaling_filter: filter,
+                ..self.info
+            },
+            ..self
+        }
+    }
+
+    /// Whether to keep the image's aspect ratio when it doesn't match the
+    /// window's.
+    ///
+    /// Defaults to `false`, which stretches the image to fill the window.
+    /// When enabled, the image is scaled as large as it fits and centered,
+    /// with black bars filling the rest of the window. Mouse positions over
+    /// the bars fall outside of the image.
+    pub fn preserve_aspect(self, enabled: bool) -> Self {
+        Self {
+            info: CanvasInfo {
+                preserve_aspect: enabled,
+                ..self.info
+            },
+            ..self
+        }
+    }
+
+    /// Render the image at a fixed resolution, independent of the window
+    /// size.
+    ///
+    /// By default the image has the same resolution as the window (taking
+    /// [`hidpi`] into account). Rendering at a lower resolution and letting
+    /// the canvas scale it up is a big speedup for expensive per-pixel
+    /// sketches. Mouse positions are scaled to match, so they stay in the
+    /// pixels of the image.
+    /// ```rust,no_run
+    /// # use pixel_canvas::prelude::*;
+    /// Canvas::new(1024, 768)
+    ///     .render_size(256, 192)
+    ///     .scaling_filter(ScalingFilter::Nearest)
+    ///     .render(|_, image| {
+    ///         image.fill_horizontal_gradient(Color::BLACK, Color::WHITE);
+    ///     });
+    /// ```
+    ///
+    /// [`hidpi`]: struct.Canvas.html#method.hidpi
+    pub fn render_size(self, width: usize, height: usize) -> Self {
+        Self {
+            info: CanvasInfo {
+                render_size: Some((width, height)),
+                ..self.info
+            },
+            ..self
+        }
+    }
+
+    /// Antialias the whole canvas by supersampling.
+    ///
+    /// Defaults to `1`, which doesn't supersample.
+    /// The render callback is given an image `factor` times larger in each
+    /// dimension, which is averaged down to the display size before it's
+    /// shown. This smooths out jagged edges in any sketch, but it costs
+    /// `factor²` times as many pixels to render, so a `factor` of 2 or 3 is
+    /// usually plenty. Positions from the input handlers, like
+    /// [`MouseState`](../input/struct.MouseState.html), are in the pixels of
+    /// the larger image, so they still index the image you draw into.
+    pub fn supersample(self, factor: u32) -> Self {
+        Self {
+            info: CanvasInfo {
+                supersample: factor,
+                ..self.info
+            },
+            ..self
+        }
+    }
+
+    /// Request multisample antialiasing (MSAA) for the window.
+    ///
+    /// Defaults to `0`, which doesn't multisample. This only smooths the
+    /// edges of geometry you draw yourself with
+    /// [`render_custom`](struct.Canvas.html#method.render_custom). The image
+    /// is copied into the window with an axis-aligned blit, so neither the
+    /// image nor its edges are changed by multisampling. The sample
+    /// count must be a power of two, so other counts are rounded down. If the
+    /// system doesn't support the requested count, a warning is printed and
+    /// the canvas opens without multisampling instead.
+    pub fn multisampling(self, samples: u16) -> Self {
+        let samples = match samples {
+            0 => 0,
+            // Round down to a power of two.
+            _ => 1 << (15 - samples.leading_zeros()),
+        };
+        Self {
+            info: CanvasInfo {
+                multisampling: samples,
+                ..self.info
+            },
+            ..self
+        }
+    }
+
+    /// Request a specific OpenGL version and profile for the context.
+    ///
+    /// By default the latest version is requested, and the platform picks
+    /// the profile. Some drivers create a context that doesn't work, and
+    /// forcing a different version, or the compatibility profile, can get
+    /// the canvas running again.
+    /// ```rust,no_run
+    /// # use pixel_canvas::prelude::*;
+    /// use pixel_canvas::glium::glutin::{Api, GlProfile, GlRequest};
+    ///
+    /// Canvas::new(512, 512)
+    ///     .gl_request(GlRequest::Specific(Api::OpenGl, (3, 3)), GlProfile::Compatibility)
+    ///     .render(|_, image| {
+    ///         // ...
+    ///     });
+    /// ```
+    pub fn gl_request(self, request: GlRequest, profile: GlProfile) -> Self {
+        Self {
+            info: CanvasInfo {
+                gl_request: request,
+                gl_profile: Some(profile),
+                ..self.info
+            },
+            ..self
+        }
+    }
+
+    /// Whether the window can be resized.
+    ///
+    /// Defaults to `false`.
+    /// When the window is resized, the canvas `width` and `height` are
+    /// updated, and the render callback is given a new image at the new
+    /// resolution. The new image starts out cleared, so you'll need to
+    /// redraw all of it.
+    pub fn resizable(self, enabled: bool) -> Self {
+        Self {
+            info: CanvasInfo {
+                resizable: enabled,
+                ..self.info
+            },
+            window: self.window.with_resizable(enabled),
+            ..self
+        }
+    }
+
+    /// Set the minimum size that the window can be resized to, in virtual
+    /// pixels.
+    ///
+    /// This only matters if the window is [`resizable`].
+    ///
+    /// [`resizable`]: struct.Canvas.html#method.resizable
+    pub fn min_size(self, width: usize, height: usize) -> Self {
+        Self {
+            window: self
+                .window
+                .with_min_inner_size(glutin::dpi::LogicalSize::new(width as f64, height as f64)),
+            ..self
+        }
+    }
+
+    /// Place the window at a position on the screen, in physical pixels from
+    /// the top left of the desktop.
+    ///
+    /// By default the platform decides where the window goes. This is useful
+    /// for installations with several windows.
+    pub fn position(self, x: i32, y: i32) -> Self {
+        Self {
+            window: self
+                .window
+                .with_position(glutin::dpi::PhysicalPosition::new(x, y)),
+            ..self
+        }
+    }
+
+    /// Whether the window stays on top of other windows.
+    ///
+    /// Defaults to `false`. This is handy for overlay displays.
+    pub fn always_on_top(self, enabled: bool) -> Self {
+        Self {
+            window: self.window.with_always_on_top(enabled),
+            ..self
+        }
+    }
+
+    /// Whether the window is shown when the canvas starts.
+    ///
+    /// Defaults to `true`. A hidden canvas still runs, so you can do your
+    /// setup in the first frames, and then show the window with
+    /// [`CanvasInfo::set_visible`](struct.CanvasInfo.html#method.set_visible).
+    /// ```rust,no_run
+    /// # use pixel_canvas::prelude::*;
+    /// Canvas::new(512, 512)
+    ///     .visible(false)
+    ///     .render_with_info(|info, _, image| {
+    ///         image.fill(Color::BLUE);
+    ///         info.set_visible(true);
+    ///     });
+    /// ```
+    pub fn visible(self, enabled: bool) -> Self {
+        Self {
+            info: CanvasInfo {
+                visible: enabled,
+                ..self.info
+            },
+            ..self
+        }
+    }
+
+    /// Whether the window starts maximized.
+    ///
+    /// Defaults to `false`. Unless the canvas is also
+    /// [`resizable`](struct.Canvas.html#method.resizable), the image keeps
+    /// its size and is stretched to fill the window.
+    pub fn maximized(self, enabled: bool) -> Self {
+        Self {
+            window: self.window.with_maximized(enabled),
+            ..self
+        }
+    }
+
+    /// Whether the window has a title bar and borders.
+    ///
+    /// Defaults to `true`. Borderless windows are useful for installations,
+    /// where only the art should be visible.
+    pub fn decorations(self, enabled: bool) -> Self {
+        Self {
+            window: self.window.with_decorations(enabled),
+            ..self
+        }
+    }
+
+    /// Whether resizing the window keeps its aspect ratio.
+    ///
+    /// Defaults to `false`.
+    /// When enabled, the window snaps back to the nearest size with the same
+    /// aspect ratio after it's resized. Event handlers still see each
+    /// `Resized` event, but the canvas size already has the snapped size.
+    /// This only matters if the window is [`resizable`].
+    ///
+    /// [`resizable`]: struct.Canvas.html#method.resizable
+    pub fn aspect_ratio_lock(self, enabled: bool) -> Self {
+        Self {
+            info: CanvasInfo {
+                aspect_ratio_lock: enabled,
+                ..self.info
+            },
+            ..self
+        }
+    }
+
+    /// Close the window after rendering a number of frames.
+    ///
+    /// This is handy for rendering a fixed-length animation, or for
+    /// benchmarking with the frame timing in [`CanvasInfo::stats`].
+    ///
+    /// [`CanvasInfo::stats`]: struct.CanvasInfo.html#structfield.stats
+    pub fn max_frames(self, frames: usize) -> Self {
+        Self {
+            info: CanvasInfo {
+                max_frames: Some(frames),
+                ..self.info
+            },
+            ..self
+        }
+    }
+
+    /// Set the seed for the random number generator given to
+    /// [`render_with_rng`](struct.Canvas.html#method.render_with_rng).
+    ///
+    /// Defaults to `0`.
+    pub fn seed(self, seed: u64) -> Self {
+        Self {
+            info: CanvasInfo { seed, ..self.info },
+            ..self
+        }
+    }
+
+    /// Whether to upload frames to the GPU through a pixel buffer object.
+    ///
+    /// Defaults to `true`.
+    /// Copying the image into a pixel buffer lets the GPU finish the
+    /// transfer to the texture asynchronously, instead of the upload
+    /// blocking until the texture is ready. Turn it off to compare, or if a
+    /// driver misbehaves with it.
+    pub fn pixel_buffer(self, enabled: bool) -> Self {
+        Self {
+            info: CanvasInfo {
+                pixel_buffer: enabled,
+                ..self.info
+            },
+            ..self
+        }
+    }
+
+    /// Write every frame to stdout as raw bytes, for piping into a video
+    /// encoder.
+    ///
+    /// Defaults to `false`.
+    /// Frames are written top row first with no header, and stdout is
+    /// flushed after each one. For the default RGB canvas, the bytes are in
+    /// the `rgb24` format, so with a 1280x720 canvas you can record a video
+    /// with:
+    /// ```sh
+    /// my_sketch | ffmpeg -f rawvideo -pix_fmt rgb24 -s 1280x720 -r 60 -i - out.mp4
+    /// ```
+    /// The frame size is printed to stderr when the canvas starts. An RGBA
+    /// canvas writes `rgba` frames, and a float canvas writes three `f32`s per
+    /// pixel. If writing fails, for example because the encoder exited, the
+    /// canvas closes.
+    ///
+    /// The encoder can't follow a change in the frame size, so if the frame
+    /// size changes, because the canvas is
+    /// [`resizable`](struct.Canvas.html#method.resizable) or a hidpi canvas
+    /// moved to a monitor with a different DPI, the canvas prints an error
+    /// and closes instead of writing the frame.
+    pub fn pipe_raw(self, enabled: bool) -> Self {
+        Self {
+            info: CanvasInfo {
+                pipe_raw: enabled,
+                ..self.info
+            },
+            ..self
+        }
+    }
+
+    /// Set the icon of the window.
+    ///
+    /// The image is converted to an opaque RGBA icon. Some platforms ignore
+    /// window icons, and if the icon can't be created the canvas prints a
+    /// warning and keeps the default icon.
+    pub fn icon(self, image: &Image) -> Self {
+        let mut rgba = Vec::with_capacity(image.len() * 4);
+        for pix in image.iter() {
+            rgba.extend_from_slice(&[pix.r, pix.g, pix.b, 255]);
+        }
+        match Icon::from_rgba(rgba, image.width() as u32, image.height() as u32) {
+            Ok(icon) => Self {
+                window: self.window.with_window_icon(Some(icon)),
+                ..self
+            },
+            Err(err) => {
+                eprintln!("pixel-canvas: couldn't set the window icon: {}", err);
+                self
+            }
+        }
+    }
+
     /// Attach an input handler.
     ///
     /// Your input handler must be compatible with any state that you've set
     /// previously. Your event handler will be called for each event with the
-    /// canvas information, the current state, and the inciting event.
-    pub fn input<NewHandler>(self, callback: NewHandler) -> Canvas<State, NewHandler>
+    /// canvas information, the current state, and the inciting event. It
+    /// returns either a `bool` saying whether the state changed, or a
+    /// [`HandlerResponse`](enum.HandlerResponse.html).
+    ///
+    /// After each frame is rendered, the handler gets an
+    /// `Event::RedrawEventsCleared`, which is useful for resetting anything
+    /// that's accumulated per frame.
+    pub fn input<NewHandler, NewResponse>(
+        self,
+        callback: NewHandler,
+    ) -> Canvas<State, NewHandler, Format, UserEvent>
     where
-        NewHandler: FnMut(&CanvasInfo, &mut State, &Event<()>) -> bool + 'static,
+        NewHandler: FnMut(&CanvasInfo, &mut State, &Event<UserEvent>) -> NewResponse + 'static,
+        NewResponse: Into<HandlerResponse>,
     {
         Canvas {
             info: self.info,
+            window: self.window,
             image: self.image,
             state: self.state,
             event_handler: callback,
+            event_loop: self.event_loop,
+            on_close: self.on_close,
+            on_resize: self.on_resize,
+            on_tick: self.on_tick,
+            clear_with: self.clear_with,
+        }
+    }
+
+    /// Set the pixel format of the image.
+    ///
+    /// Defaults to RGB [`Color`]s. Use this to draw into an RGBA image, or a
+    /// single channel `u8` image that's displayed in grayscale. This resets
+    /// the [`clear_with`](struct.Canvas.html#method.clear_with) callback,
+    /// since it takes the old image.
+    /// ```rust,no_run
+    /// # use pixel_canvas::prelude::*;
+    /// Canvas::new(256, 256)
+    ///     .pixel_format::<u8>()
+    ///     .render(|_, heights| {
+    ///         for (i, height) in heights.iter_mut().enumerate() {
+    ///             *height = (i % 256) as u8;
+    ///         }
+    ///     });
+    /// ```
+    ///
+    /// [`Color`]: ../color/struct.Color.html
+    pub fn pixel_format<NewFormat: Pixel>(self) -> Canvas<State, Handler, NewFormat, UserEvent> {
+        Canvas {
+            info: self.info,
+            window: self.window,
+            image: Image::filled(
+                self.image.width(),
+                self.image.height(),
+                NewFormat::default(),
+            ),
+            state: self.state,
+            event_handler: self.event_handler,
+            event_loop: self.event_loop,
+            on_close: self.on_close,
+            on_resize: self.on_resize,
+            on_tick: self.on_tick,
+            clear_with: None,
+        }
+    }
+
+    /// Provide a callback that's called with the state when the canvas
+    /// closes.
+    ///
+    /// It's called once, whether the window was closed, an event handler
+    /// exited, or the [`max_frames`](struct.Canvas.html#method.max_frames)
+    /// were rendered. This is a good place to save progress or flush a log.
+    /// Since it takes the state, set it after
+    /// [`state`](struct.Canvas.html#method.state), which resets it.
+    /// ```rust,no_run
+    /// # use pixel_canvas::prelude::*;
+    /// Canvas::new(512, 512)
+    ///     .state(0u64)
+    ///     .on_close(|frames| println!("rendered {} frames", frames))
+    ///     .render(|frames, image| {
+    ///         *frames += 1;
+    ///         // ...
+    ///     });
+    /// ```
+    pub fn on_close(self, callback: impl FnOnce(&mut State) + 'static) -> Self {
+        Self {
+            on_close: Some(Box::new(callback)),
+            ..self
+        }
+    }
+
+    /// The size of the image the render callback will be given, in physical
+    /// pixels.
+    ///
+    /// With [`hidpi`](struct.Canvas.html#method.hidpi) enabled, the size
+    /// depends on the scale factor of the monitor, which normally isn't known
+    /// until the window is created. This looks up the scale factor of the
+    /// primary monitor early, so you can size your own buffers to match
+    /// before rendering. If the window opens on another monitor, or is
+    /// resized, the image size can still change once it's running. The size
+    /// includes the [`render_size`](struct.Canvas.html#method.render_size)
+    /// and [`supersample`](struct.Canvas.html#method.supersample) settings.
+    /// ```rust,no_run
+    /// # use pixel_canvas::prelude::*;
+    /// let mut canvas = Canvas::new(512, 512).hidpi(true);
+    /// let (width, height) = canvas.physical_size();
+    /// let canvas = canvas.state(Image::new(width, height));
+    /// ```
+    pub fn physical_size(&mut self) -> (usize, usize) {
+        let event_loop = self
+            .event_loop
+            .get_or_insert_with(EventLoop::with_user_event);
+        let monitor = event_loop
+            .primary_monitor()
+            .or_else(|| event_loop.available_monitors().next());
+        self.info
+            .set_scale_factor(monitor.map_or(1.0, |monitor| monitor.scale_factor()));
+        let (width, height) = self.info.image_size();
+        let factor = self.info.supersample.max(1) as usize;
+        (width * factor, height * factor)
+    }
+
+    /// Provide a callback that's called with the state whenever the size of
+    /// the image changes, with the new width and height.
+    ///
+    /// The image is reallocated when the window is
+    /// [`resizable`](struct.Canvas.html#method.resizable) and gets resized,
+    /// or when it moves to a monitor with a different scale factor with
+    /// [`hidpi`](struct.Canvas.html#method.hidpi) enabled. This is a good
+    /// place to resize anything in your state that matches the image. It
+    /// isn't called for the initial size, see
+    /// [`physical_size`](struct.Canvas.html#method.physical_size) for that.
+    /// Since it takes the state, set it after
+    /// [`state`](struct.Canvas.html#method.state), which resets it.
+    /// ```rust,no_run
+    /// # use pixel_canvas::prelude::*;
+    /// let mut canvas = Canvas::new(512, 512).resizable(true);
+    /// let (width, height) = canvas.physical_size();
+    /// canvas
+    ///     .state(vec![0.0f32; width * height])
+    ///     .on_resize(|grid, width, height| grid.resize(width * height, 0.0))
+    ///     .render(|grid, image| {
+    ///         // ...
+    ///     });
+    /// ```
+    pub fn on_resize(self, callback: impl FnMut(&mut State, usize, usize) + 'static) -> Self {
+        Self {
+            on_resize: Some(Box::new(callback)),
+            ..self
+        }
+    }
+
+    /// Provide a callback that's called with the state once per frame tick,
+    /// whether or not a frame is rendered, returning whether the state
+    /// changed.
+    ///
+    /// This is for input that doesn't arrive through window events, like
+    /// gamepads. Unlike the render callback, it keeps being called with
+    /// [`render_on_change`](struct.Canvas.html#method.render_on_change), and
+    /// returning `true` requests a redraw. It isn't called while rendering is
+    /// [paused](struct.CanvasInfo.html#structfield.paused), since the canvas
+    /// sleeps until an event arrives. Since it takes the state, set it after
+    /// [`state`](struct.Canvas.html#method.state), which resets it.
+    /// ```rust,no_run
+    /// # use pixel_canvas::prelude::*;
+    /// use std::sync::mpsc;
+    ///
+    /// let (sender, receiver) = mpsc::channel();
+    /// std::thread::spawn(move || sender.send(Color::WHITE));
+    /// Canvas::new(512, 512)
+    ///     .render_on_change(true)
+    ///     .state(Color::BLACK)
+    ///     .on_tick(move |color| match receiver.try_recv() {
+    ///         Ok(new) => {
+    ///             *color = new;
+    ///             true
+    ///         }
+    ///         Err(_) => false,
+    ///     })
+    ///     .render(|color, image| image.fill(*color));
+    /// ```
+    pub fn on_tick(self, callback: impl FnMut(&mut State) -> bool + 'static) -> Self {
+        Self {
+            on_tick: Some(Box::new(callback)),
+            ..self
+        }
+    }
+
+    /// Get a handle to request a redraw from outside an event handler.
+    ///
+    /// See [`RedrawRequester`](struct.RedrawRequester.html).
+    pub fn redraw_requester(&mut self) -> RedrawRequester<UserEvent> {
+        let event_loop = self
+            .event_loop
+            .get_or_insert_with(EventLoop::with_user_event);
+        RedrawRequester {
+            proxy: event_loop.create_proxy(),
+        }
+    }
+
+    /// Set the type of your own events, sent with an [`EventProxy`].
+    ///
+    /// Defaults to `()`.
+    /// Since the event handler takes the event type, this resets the event
+    /// handler, so call it before [`input`]. The built-in handlers in the
+    /// [`input`](../input/index.html) module work with any event type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it's called after [`redraw_requester`], [`event_proxy`], or
+    /// [`physical_size`], which create the event loop.
+    ///
+    /// [`EventProxy`]: struct.EventProxy.html
+    /// [`input`]: struct.Canvas.html#method.input
+    /// [`redraw_requester`]: struct.Canvas.html#method.redraw_requester
+    /// [`event_proxy`]: struct.Canvas.html#method.event_proxy
+    /// [`physical_size`]: struct.Canvas.html#method.physical_size
+    pub fn user_event<NewEvent>(
+        self,
+    ) -> Canvas<State, EventHandler<State, NewEvent>, Format, NewEvent> {
+        assert!(
+            self.event_loop.is_none(),
+            "the user event type must be set before creating the event loop"
+        );
+        Canvas {
+            info: self.info,
+            window: self.window,
+            image: self.image,
+            state: self.state,
+            event_handler: |_, _, _| false,
+            event_loop: None,
+            on_close: self.on_close,
+            on_resize: self.on_resize,
+            on_tick: self.on_tick,
+            clear_with: self.clear_with,
+        }
+    }
+
+    /// Get a handle to send your own events to the event handler.
+    ///
+    /// See [`EventProxy`](struct.EventProxy.html).
+    pub fn event_proxy(&mut self) -> EventProxy<UserEvent> {
+        let event_loop = self
+            .event_loop
+            .get_or_insert_with(EventLoop::with_user_event);
+        EventProxy {
+            proxy: event_loop.create_proxy(),
         }
     }
 
@@ -205,104 +1552,657 @@ where
     /// current state and a reference to the image. Depending on settings,
     /// this will either be called at 60fps, or only called when state changes.
     /// See [`render_on_change`](struct.Canvas.html#method.render_on_change).
-    pub fn render(mut self, mut callback: impl FnMut(&mut State, &mut Image) + 'static) {
-        let event_loop = glutin::event_loop::EventLoop::new();
-        let wb = glutin::window::WindowBuilder::new()
+    ///
+    /// This panics if the window can't be created, see
+    /// [`try_render`](struct.Canvas.html#method.try_render) to handle that.
+    pub fn render(self, mut callback: impl FnMut(&mut State, &mut Image<Format>) + 'static) {
+        self.render_with_info(move |_, state, image| callback(state, image))
+    }
+
+    /// Provide a rendering callback, returning an error if the canvas can't
+    /// render.
+    ///
+    /// This behaves just like [`render`](struct.Canvas.html#method.render),
+    /// but returns a [`CanvasError`] if the window can't be created, so you
+    /// can print a helpful message or fall back to rendering without a
+    /// window. Once the window is open the event loop takes over the thread
+    /// and never returns, so if a frame can't be drawn, the error is printed
+    /// and the canvas closes.
+    /// ```rust,no_run
+    /// # use pixel_canvas::prelude::*;
+    /// let result = Canvas::new(512, 512).try_render(|_, image| {
+    ///     image.fill(Color::BLUE);
+    /// });
+    /// if let Err(err) = result {
+    ///     eprintln!("can't show the canvas: {}", err);
+    /// }
+    /// ```
+    ///
+    /// [`CanvasError`]: enum.CanvasError.html
+    pub fn try_render(
+        self,
+        mut callback: impl FnMut(&mut State, &mut Image<Format>) + 'static,
+    ) -> Result<(), CanvasError> {
+        self.try_render_with_info(move |_, state, image| callback(state, image))
+    }
+
+    /// Provide a rendering callback that also receives the canvas information.
+    ///
+    /// This behaves just like [`render`](struct.Canvas.html#method.render),
+    /// but is useful if you need to know things like the `dpi` while
+    /// drawing, for example to scale line widths.
+    /// ```rust,no_run
+    /// # use pixel_canvas::prelude::*;
+    /// Canvas::new(512, 512).hidpi(true).render_with_info(|info, _, image| {
+    ///     let thickness = (4.0 * info.dpi) as usize;
+    ///     for row in image.rows_mut().take(thickness) {
+    ///         row.iter_mut().for_each(|pix| *pix = Color::WHITE);
+    ///     }
+    /// });
+    /// ```
+    pub fn render_with_info(
+        self,
+        callback: impl FnMut(&CanvasInfo, &mut State, &mut Image<Format>) + 'static,
+    ) {
+        if let Err(err) = self.try_render_with_info(callback) {
+            panic!("pixel-canvas: {}", err);
+        }
+    }
+
+    /// Provide a rendering callback that also receives the canvas
+    /// information, returning an error if the canvas can't render.
+    ///
+    /// See [`try_render`](struct.Canvas.html#method.try_render) for the
+    /// errors.
+    pub fn try_render_with_info(
+        mut self,
+        mut callback: impl FnMut(&CanvasInfo, &mut State, &mut Image<Format>) + 'static,
+    ) -> Result<(), CanvasError> {
+        let mut clear_with = self.clear_with.take();
+        self.run(
+            Schedule::Fixed,
+            move |info, state, image| {
+                clear(info, &mut clear_with, state, image);
+                callback(info, state, image);
+                true
+            },
+            no_draw,
+        )
+    }
+
+    /// Provide a rendering callback that decides when frames are shown.
+    ///
+    /// Instead of rendering at a fixed framerate, the canvas calls your
+    /// callback over and over as fast as it can, and only shows the image
+    /// when the callback returns `true`. Returning `false` skips presenting,
+    /// and the image is kept as-is for the next call. This is useful when
+    /// frames are driven by your own timing, like syncing to audio.
+    ///
+    /// Since the callback is polled continuously, it should wait or return
+    /// quickly when it has nothing to do. The
+    /// [`clear_color`](struct.Canvas.html#method.clear_color) and
+    /// [`clear_with`](struct.Canvas.html#method.clear_with) are only applied
+    /// after a frame is presented, and
+    /// [`render_on_change`](struct.Canvas.html#method.render_on_change) has
+    /// no effect.
+    /// ```rust,no_run
+    /// # use pixel_canvas::prelude::*;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let mut next_beat = Instant::now();
+    /// let mut flash = false;
+    /// Canvas::new(512, 512).render_manual(move |_, image| {
+    ///     if Instant::now() < next_beat {
+    ///         return false;
+    ///     }
+    ///     next_beat += Duration::from_millis(500);
+    ///     flash = !flash;
+    ///     image.fill(if flash { Color::WHITE } else { Color::BLACK });
+    ///     true
+    /// });
+    /// ```
+    pub fn render_manual(
+        mut self,
+        mut callback: impl FnMut(&mut State, &mut Image<Format>) -> bool + 'static,
+    ) {
+        let mut clear_with = self.clear_with.take();
+        let mut presented = true;
+        let result = self.run(
+            Schedule::Manual,
+            move |info, state, image| {
+                if presented {
+                    clear(info, &mut clear_with, state, image);
+                }
+                presented = callback(state, image);
+                presented
+            },
+            no_draw,
+        );
+        if let Err(err) = result {
+            panic!("pixel-canvas: {}", err);
+        }
+    }
+
+    /// Provide a rendering callback that also receives a seeded random number
+    /// generator.
+    ///
+    /// The generator is created once from the [`seed`], and kept between
+    /// frames, so the same seed always renders the same sequence of frames.
+    /// See [`Rng`] for the algorithm used.
+    /// ```rust,no_run
+    /// # use pixel_canvas::prelude::*;
+    /// Canvas::new(512, 512)
+    ///     .seed(1234)
+    ///     .render_with_rng(|_, image, rng| {
+    ///         for pix in image.iter_mut() {
+    ///             *pix = Color::gray((rng.next_f32() * 255.0) as u8);
+    ///         }
+    ///     });
+    /// ```
+    ///
+    /// [`seed`]: struct.Canvas.html#method.seed
+    /// [`Rng`]: ../math/struct.Rng.html
+    pub fn render_with_rng(
+        self,
+        mut callback: impl FnMut(&mut State, &mut Image<Format>, &mut Rng) + 'static,
+    ) {
+        let mut rng = Rng::new(self.info.seed);
+        self.render_with_info(move |_, state, image| callback(state, image, &mut rng))
+    }
+
+    /// Provide a rendering callback that can read the previous frame.
+    ///
+    /// The canvas keeps two images and swaps them every frame. Your callback
+    /// gets the previous frame to read from, and the next frame to draw into,
+    /// which is what gets displayed. This is useful for feedback effects,
+    /// cellular automata, and reaction-diffusion, where each frame is computed
+    /// from the last one.
+    ///
+    /// The next frame initially contains the frame from before the previous
+    /// one, unless a [`clear_color`](struct.Canvas.html#method.clear_color)
+    /// or [`clear_with`](struct.Canvas.html#method.clear_with) callback is
+    /// set.
+    /// ```rust,no_run
+    /// # use pixel_canvas::prelude::*;
+    /// Canvas::new(512, 512).render_feedback(|_, prev, next| {
+    ///     // Fade out the previous frame to leave trails.
+    ///     for (pix, old) in next.iter_mut().zip(prev.iter()) {
+    ///         *pix = *old * 0.95;
+    ///     }
+    /// });
+    /// ```
+    pub fn render_feedback(
+        mut self,
+        mut callback: impl FnMut(&mut State, &Image<Format>, &mut Image<Format>) + 'static,
+    ) {
+        let mut clear_with = self.clear_with.take();
+        let mut prev = Image::filled(0, 0, Format::default());
+        let result = self.run(
+            Schedule::Fixed,
+            move |info, state, next| {
+                if prev.width() != next.width() || prev.height() != next.height() {
+                    prev = next.clone();
+                }
+                std::mem::swap(&mut prev, next);
+                clear(info, &mut clear_with, state, next);
+                callback(state, &prev, next);
+                true
+            },
+            no_draw,
+        );
+        if let Err(err) = result {
+            panic!("pixel-canvas: {}", err);
+        }
+    }
+
+    /// Provide a rendering callback, and a callback to draw your own OpenGL
+    /// on top of the image.
+    ///
+    /// Every frame, `render` draws into the image like with
+    /// [`render`](struct.Canvas.html#method.render), the image is drawn to
+    /// the window, and then `draw` is called with the `glium` display and
+    /// the frame being drawn, so you can issue your own draw calls before
+    /// the frame is shown. This is an escape hatch for things like shader
+    /// based post effects.
+    ///
+    /// The display and frame are only borrowed for each call, and the frame
+    /// can't be kept, since it's finished right after `draw` returns. The
+    /// display lives as long as the canvas, so GL objects created from it,
+    /// like programs and vertex buffers, can be kept in your state. Since the
+    /// state is created before the display exists, keep them in an `Option`
+    /// and create them on the first call.
+    /// ```rust,no_run
+    /// # use pixel_canvas::prelude::*;
+    /// use pixel_canvas::glium::{self, Surface};
+    ///
+    /// Canvas::new(512, 512).render_custom(
+    ///     |_, image| image.fill(Color::BLUE),
+    ///     |_, _display, target| {
+    ///         // Tint the top of the window red with a scissored clear.
+    ///         let top = glium::Rect { left: 0, bottom: 256, width: 512, height: 256 };
+    ///         target.clear(Some(&top), Some((1.0, 0.0, 0.0, 1.0)), false, None, None);
+    ///     },
+    /// );
+    /// ```
+    pub fn render_custom(
+        mut self,
+        mut render: impl FnMut(&mut State, &mut Image<Format>) + 'static,
+        draw: impl FnMut(&mut State, &glium::Display, &mut glium::Frame) + 'static,
+    ) {
+        let mut clear_with = self.clear_with.take();
+        let result = self.run(
+            Schedule::Fixed,
+            move |info, state, image| {
+                clear(info, &mut clear_with, state, image);
+                render(state, image);
+                true
+            },
+            draw,
+        );
+        if let Err(err) = result {
+            panic!("pixel-canvas: {}", err);
+        }
+    }
+
+    fn run(
+        mut self,
+        schedule: Schedule,
+        mut callback: impl FnMut(&CanvasInfo, &mut State, &mut Image<Format>) -> bool + 'static,
+        mut draw: impl FnMut(&mut State, &glium::Display, &mut glium::Frame) + 'static,
+    ) -> Result<(), CanvasError> {
+        let event_loop = self
+            .event_loop
+            .take()
+            .unwrap_or_else(EventLoop::with_user_event);
+        let wb = self
+            .window
+            .clone()
             .with_title(&self.info.title)
+            .with_visible(self.info.visible)
             .with_inner_size(glutin::dpi::LogicalSize::new(
                 self.info.width as f64,
                 self.info.height as f64,
-            ))
-            .with_resizable(false);
-        let cb = glutin::ContextBuilder::new().with_vsync(true);
-        let display = glium::Display::new(wb, cb, &event_loop).unwrap();
+            ));
+        let mut cb = glutin::ContextBuilder::new()
+            .with_vsync(true)
+            .with_gl(self.info.gl_request);
+        if let Some(profile) = self.info.gl_profile {
+            cb = cb.with_gl_profile(profile);
+        }
+        let display = match self.info.multisampling {
+            0 => glium::Display::new(wb, cb, &event_loop),
+            samples => glium::Display::new(
+                wb.clone(),
+                cb.clone().with_multisampling(samples),
+                &event_loop,
+            )
+            .or_else(|err| {
+                eprintln!(
+                    "pixel-canvas: couldn't enable {}x multisampling: {}",
+                    samples, err
+                );
+                self.info.multisampling = 0;
+                glium::Display::new(wb, cb, &event_loop)
+            }),
+        }
+        .map_err(CanvasError::Display)?;
 
-        self.info.dpi = if self.info.hidpi {
-            display.gl_window().window().scale_factor()
-        } else {
-            1.0
-        };
+        {
+            let gl_window = display.gl_window();
+            let window = gl_window.window();
+            window.set_cursor_visible(!self.info.hide_cursor);
+            if self.info.grab_cursor {
+                if let Err(err) = window.set_cursor_grab(true) {
+                    eprintln!("pixel-canvas: couldn't grab the cursor: {}", err);
+                }
+            }
+        }
 
-        let width = (self.info.width as f64 * self.info.dpi) as usize;
-        let height = (self.info.height as f64 * self.info.dpi) as usize;
-        self.image = Image::new(width, height);
+        self.info
+            .set_scale_factor(display.gl_window().window().scale_factor());
 
-        let mut texture = glium::Texture2d::empty_with_format(
-            &display,
-            glium::texture::UncompressedFloatFormat::U8U8U8,
-            glium::texture::MipmapsOption::NoMipmap,
-            width as u32,
-            height as u32,
-        )
-        .unwrap();
+        let (width, height) = self.info.image_size();
+        let factor = self.info.supersample.max(1) as usize;
+        self.image = Image::filled(width * factor, height * factor, Format::default());
+        if self.info.pipe_raw {
+            eprintln!(
+                "pixel-canvas: writing {}x{} frames to stdout",
+                width, height
+            );
+        }
+        let piped_size = (width as u32, height as u32);
+
+        // Alternate between two textures, so that uploading the next frame
+        // doesn't have to wait for the GPU to finish drawing the last one.
+        let mut textures = [
+            empty_texture::<Format>(&display, width as u32, height as u32),
+            empty_texture::<Format>(&display, width as u32, height as u32),
+        ];
+        let mut pixel_buffers = [
+            PixelBuffer::new_empty(&display, width * height),
+            PixelBuffer::new_empty(&display, width * height),
+        ];
+        let mut current = 0;
+        // The regions of each texture that are out of date, or `None` if the
+        // whole texture needs to be uploaded.
+        let mut stale: [Option<Vec<Rect>>; 2] = [None, None];
 
         let mut next_frame_time = Instant::now();
         let mut should_render = true;
-        event_loop.run(move |event, _, control_flow| match event {
-            Event::NewEvents(StartCause::ResumeTimeReached { .. })
-            | Event::NewEvents(StartCause::Init) => {
-                next_frame_time = next_frame_time + Duration::from_nanos(16_666_667);
-                *control_flow = ControlFlow::WaitUntil(next_frame_time);
-                if !should_render {
-                    return;
-                }
-                if self.info.render_on_change {
-                    should_render = false;
+        let mut shown_title = self.info.title.clone();
+        let mut shown_visible = self.info.visible;
+        event_loop.run(move |event, _, control_flow| {
+            if let Event::LoopDestroyed = event {
+                if let Some(on_close) = self.on_close.take() {
+                    on_close(&mut self.state);
                 }
-                let frame_start = Instant::now();
-
-                callback(&mut self.state, &mut self.image);
-                let width = self.image.width() as u32;
-                let height = self.image.height() as u32;
-                if width != texture.width() || height != texture.height() {
-                    texture = glium::Texture2d::empty_with_format(
-                        &display,
-                        glium::texture::UncompressedFloatFormat::U8U8U8,
-                        glium::texture::MipmapsOption::NoMipmap,
-                        width,
-                        height,
-                    )
-                    .unwrap();
-                    display
-                        .gl_window()
-                        .window()
-                        .set_inner_size(glutin::dpi::LogicalSize::new(width as f64, height as f64));
+            }
+            if let Event::WindowEvent { event, .. } = &event {
+                let resized = match event {
+                    // The window moved to a monitor with a different DPI.
+                    WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                        self.info.set_scale_factor(*scale_factor);
+                        true
+                    }
+                    WindowEvent::Resized(size)
+                        if self.info.resizable && size.width > 0 && size.height > 0 =>
+                    {
+                        let mut size = size.to_logical::<f64>(self.info.scale_factor);
+                        if self.info.aspect_ratio_lock {
+                            let (old_w, old_h) = (self.info.width as f64, self.info.height as f64);
+                            let scale = (size.width / old_w + size.height / old_h) / 2.0;
+                            let (w, h) = (old_w * scale, old_h * scale);
+                            if (w - size.width).abs() >= 1.0 || (h - size.height).abs() >= 1.0 {
+                                // Snap back, and use the snapped size right away, so the
+                                // resize event that follows doesn't change anything.
+                                size = glutin::dpi::LogicalSize::new(w, h);
+                                display.gl_window().window().set_inner_size(size);
+                            }
+                        }
+                        self.info.width = size.width.round() as usize;
+                        self.info.height = size.height.round() as usize;
+                        true
+                    }
+                    _ => false,
+                };
+                let (width, height) = self.info.image_size();
+                if resized && (width as u32, height as u32) != textures[current].dimensions() {
+                    // Reallocate the image at the new resolution.
+                    self.image = Image::filled(width * factor, height * factor, Format::default());
+                    textures = [
+                        empty_texture::<Format>(&display, width as u32, height as u32),
+                        empty_texture::<Format>(&display, width as u32, height as u32),
+                    ];
+                    stale = [None, None];
+                    should_render = true;
+                    if let Some(on_resize) = &mut self.on_resize {
+                        on_resize(&mut self.state, self.image.width(), self.image.height());
+                    }
                 }
-                texture.write(
-                    Rect {
-                        left: 0,
-                        bottom: 0,
-                        width: width as u32,
-                        height: height as u32,
-                    },
-                    &self.image,
-                );
+            }
+            let event =
+                match event {
+                    Event::NewEvents(StartCause::ResumeTimeReached { .. })
+                    | Event::NewEvents(StartCause::Init)
+                    | Event::NewEvents(StartCause::Poll) => {
+                        if self.info.paused {
+                            // Sleep until an event arrives, instead of ticking.
+                            *control_flow = ControlFlow::Wait;
+                            return;
+                        }
+                        if let Some(on_tick) = &mut self.on_tick {
+                            if on_tick(&mut self.state) {
+                                should_render = true;
+                            }
+                        }
+                        match schedule {
+                            Schedule::Fixed => {
+                                next_frame_time = next_frame_time + FRAME_BUDGET;
+                                *control_flow = ControlFlow::WaitUntil(next_frame_time);
+                                if !should_render {
+                                    return;
+                                }
+                                if self.info.render_on_change {
+                                    should_render = false;
+                                }
+                            }
+                            Schedule::Manual => *control_flow = ControlFlow::Poll,
+                        }
+                        let frame_start = Instant::now();
+
+                        if !callback(&self.info, &mut self.state, &mut self.image) {
+                            return;
+                        }
+                        let dirty = self.image.take_dirty();
+                        let downsampled;
+                        let image = if factor > 1 {
+                            downsampled = self.image.downsample(factor);
+                            &downsampled
+                        } else {
+                            &self.image
+                        };
+                        let width = image.width() as u32;
+                        let height = image.height() as u32;
+                        if (width, height) != textures[current].dimensions() {
+                            textures = [
+                                empty_texture::<Format>(&display, width, height),
+                                empty_texture::<Format>(&display, width, height),
+                            ];
+                            stale = [None, None];
+                            display.gl_window().window().set_inner_size(
+                                glutin::dpi::LogicalSize::new(width as f64, height as f64),
+                            );
+                        }
+                        for regions in &mut stale {
+                            *regions = match regions.take() {
+                                Some(mut regions) if factor == 1 && !dirty.is_empty() => {
+                                    regions.extend_from_slice(&dirty);
+                                    Some(regions)
+                                }
+                                _ => None,
+                            };
+                        }
+                        current = 1 - current;
+                        let texture = &textures[current];
+                        if let Some(regions) = stale[current].replace(Vec::new()) {
+                            // The other texture was written last frame, so this
+                            // one also needs the regions that changed then.
+                            for rect in &regions {
+                                texture.write(*rect, image.region(rect));
+                            }
+                        } else if self.info.pixel_buffer {
+                            let pixels = Format::upload(image);
+                            let pixel_buffer = &mut pixel_buffers[current];
+                            if pixel_buffer.len() != pixels.len() {
+                                *pixel_buffer = PixelBuffer::new_empty(&display, pixels.len());
+                            }
+                            pixel_buffer.write(&pixels);
+                            texture.main_level().raw_upload_from_pixel_buffer(
+                                pixel_buffer.as_slice(),
+                                0..width,
+                                0..height,
+                                0..1,
+                            );
+                        } else {
+                            texture.write(
+                                Rect {
+                                    left: 0,
+                                    bottom: 0,
+                                    width,
+                                    height,
+                                },
+                                image,
+                            );
+                        }
+
+                        let mut target = display.draw();
+                        let filter = self.info.scaling_filter.magnify_filter();
+                        if self.info.preserve_aspect {
+                            target.clear_color(0.0, 0.0, 0.0, 1.0);
+                            let rect = letterbox(target.get_dimensions(), (width, height));
+                            texture
+                                .as_surface()
+                                .blit_whole_color_to(&target, &rect, filter);
+                        } else {
+                            texture.as_surface().fill(&target, filter);
+                        }
+                        draw(&mut self.state, &display, &mut target);
+                        // Stop timing before the swap, which blocks on vsync.
+                        let frame_end = Instant::now();
+                        if let Err(err) = target.finish() {
+                            eprintln!("pixel-canvas: {}", CanvasError::Draw(err));
+                            *control_flow = ControlFlow::Exit;
+                            return;
+                        }
 
-                let target = display.draw();
-                texture
-                    .as_surface()
-                    .fill(&target, glium::uniforms::MagnifySamplerFilter::Linear);
-                target.finish().unwrap();
-
-                let frame_end = Instant::now();
-                if self.info.show_ms {
-                    display.gl_window().window().set_title(&format!(
-                        "{} - {:3}ms",
-                        self.info.title,
-                        frame_end.duration_since(frame_start).as_millis()
-                    ));
+                        if self.info.pipe_raw {
+                            if (width, height) != piped_size {
+                                eprintln!(
+                                    "pixel-canvas: the frame size changed from {}x{} to {}x{}, \
+                                     stopping the raw output",
+                                    piped_size.0, piped_size.1, width, height
+                                );
+                                *control_flow = ControlFlow::Exit;
+                                return;
+                            }
+                            if let Err(err) = write_frame(image, io::stdout().lock()) {
+                                eprintln!("pixel-canvas: couldn't write a frame: {}", err);
+                                *control_flow = ControlFlow::Exit;
+                            }
+                        }
+
+                        self.info
+                            .stats
+                            .record(frame_end.duration_since(frame_start));
+                        if self.info.show_ms {
+                            self.info.title_stale = true;
+                        }
+                        if let Some(max_frames) = self.info.max_frames {
+                            if self.info.stats.frames >= max_frames as u64 {
+                                *control_flow = ControlFlow::Exit;
+                            }
+                        }
+                        // Let the event handler know a frame was rendered.
+                        Some(Event::RedrawEventsCleared)
+                    }
+                    glutin::event::Event::WindowEvent {
+                        event: glutin::event::WindowEvent::CloseRequested,
+                        ..
+                    } => {
+                        *control_flow = ControlFlow::Exit;
+                        None
+                    }
+                    Event::UserEvent(CanvasEvent::Redraw) => {
+                        should_render = true;
+                        None
+                    }
+                    // The canvas doesn't use redraw requests, so instead this is
+                    // sent after each rendered frame.
+                    Event::RedrawEventsCleared => None,
+                    Event::UserEvent(CanvasEvent::User(event)) => Some(Event::UserEvent(event)),
+                    event => match event.map_nonuser_event() {
+                        Ok(event) => Some(event),
+                        Err(_) => unreachable!(),
+                    },
+                };
+            if let Some(event) = event {
+                match (self.event_handler)(&self.info, &mut self.state, &event).into() {
+                    HandlerResponse::Ignore => {}
+                    HandlerResponse::Redraw => should_render = true,
+                    HandlerResponse::Exit => *control_flow = ControlFlow::Exit,
+                    HandlerResponse::Pause => self.info.paused = true,
+                    HandlerResponse::Resume => {
+                        if self.info.paused {
+                            self.info.paused = false;
+                            next_frame_time = Instant::now();
+                            *control_flow = ControlFlow::WaitUntil(next_frame_time);
+                        }
+                        should_render = true;
+                    }
                 }
             }
-            glutin::event::Event::WindowEvent {
-                event: glutin::event::WindowEvent::CloseRequested,
-                ..
-            } => {
-                *control_flow = ControlFlow::Exit;
+            if self.info.apply_changes() {
+                should_render = true;
+            }
+            if self.info.title_stale {
+                self.info.title_stale = false;
+                let title = self.info.window_title();
+                if title != shown_title {
+                    display.gl_window().window().set_title(&title);
+                    shown_title = title;
+                }
             }
-            event => {
-                let changed = (self.event_handler)(&self.info, &mut self.state, &event);
-                should_render = changed || !self.info.render_on_change;
+            if self.info.visible != shown_visible {
+                display.gl_window().window().set_visible(self.info.visible);
+                shown_visible = self.info.visible;
             }
         })
     }
 }
+
+/// The draw callback for canvases that only draw their image.
+fn no_draw<State>(_: &mut State, _: &glium::Display, _: &mut glium::Frame) {}
+
+/// Clear the image before the render callback is called.
+fn clear<State, Format: Pixel>(
+    info: &CanvasInfo,
+    clear_with: &mut Option<ClearCallback<State, Format>>,
+    state: &State,
+    image: &mut Image<Format>,
+) {
+    if let Some(color) = info.clear_color {
+        image.fill(Format::from_color(color));
+    }
+    if let Some(clear_with) = clear_with {
+        clear_with(image, state);
+    }
+}
+
+/// Write the raw bytes of an image, from the top row down.
+fn write_frame<Format: Pixel>(image: &Image<Format>, mut w: impl Write) -> io::Result<()> {
+    let texels = Format::upload(image);
+    // Safe because pixel values are plain data in their client format.
+    let bytes = unsafe {
+        std::slice::from_raw_parts(
+            texels.as_ptr() as *const u8,
+            texels.len() * std::mem::size_of::<Format::Texel>(),
+        )
+    };
+    let row_len = bytes.len() / image.height().max(1);
+    for row in bytes.chunks(row_len.max(1)).rev() {
+        w.write_all(row)?;
+    }
+    w.flush()
+}
+
+/// Create an empty texture to display images of a pixel format.
+fn empty_texture<Format: Pixel>(
+    display: &glium::Display,
+    width: u32,
+    height: u32,
+) -> glium::Texture2d {
+    glium::Texture2d::empty_with_format(
+        display,
+        Format::TEXTURE_FORMAT,
+        glium::texture::MipmapsOption::NoMipmap,
+        width,
+        height,
+    )
+    .unwrap()
+}
+
+/// The largest rectangle with the aspect ratio of `image` that fits centered
+/// in `window`.
+pub(crate) fn letterbox(window: (u32, u32), image: (u32, u32)) -> BlitTarget {
+    let (window_w, window_h) = window;
+    let (image_w, image_h) = image;
+    let scale = (window_w as f64 / image_w as f64).min(window_h as f64 / image_h as f64);
+    let width = (image_w as f64 * scale).round() as u32;
+    let height = (image_h as f64 * scale).round() as u32;
+    BlitTarget {
+        left: window_w.saturating_sub(width) / 2,
+        bottom: window_h.saturating_sub(height) / 2,
+        width: width as i32,
+        height: height as i32,
+    }
+}
@@ -1,13 +1,24 @@
 //! Types and utilities to represent colors.
 
-use std::ops::{Add, Mul, Sub};
+use crate::{math::Restrict, vector::Vec3};
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, MulAssign, Sub, SubAssign};
 
 // @Todo: Explain colors.
 
+/// The gamma used to convert between [`Color`] and [`LinearColor`].
+///
+/// This is the common approximation of the sRGB transfer function, the
+/// conversions are `linear = (c / 255) ^ 2.2` and
+/// `c = 255 * linear ^ (1 / 2.2)`.
+///
+/// [`Color`]: struct.Color.html
+/// [`LinearColor`]: struct.LinearColor.html
+pub const GAMMA: f32 = 2.2;
+
 /// A single RGB-888 color.
 // This must be repr(C) in order to directly upload to the GPU.
 #[repr(C)]
-#[derive(Copy, Clone, Default)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Color {
     /// The red component.
     pub r: u8,
@@ -26,11 +37,779 @@ impl Color {
         g: 255,
         b: 255,
     };
+    /// The color red.
+    pub const RED: Color = Color { r: 255, g: 0, b: 0 };
+    /// The color green.
+    pub const GREEN: Color = Color { r: 0, g: 255, b: 0 };
+    /// The color blue.
+    pub const BLUE: Color = Color { r: 0, g: 0, b: 255 };
+    /// The color yellow.
+    pub const YELLOW: Color = Color {
+        r: 255,
+        g: 255,
+        b: 0,
+    };
+    /// The color cyan.
+    pub const CYAN: Color = Color {
+        r: 0,
+        g: 255,
+        b: 255,
+    };
+    /// The color magenta.
+    pub const MAGENTA: Color = Color {
+        r: 255,
+        g: 0,
+        b: 255,
+    };
+    /// The color gray.
+    pub const GRAY: Color = Color {
+        r: 128,
+        g: 128,
+        b: 128,
+    };
+    /// The color orange.
+    pub const ORANGE: Color = Color {
+        r: 255,
+        g: 165,
+        b: 0,
+    };
+    /// The color purple.
+    pub const PURPLE: Color = Color {
+        r: 128,
+        g: 0,
+        b: 128,
+    };
 
     /// A convenience constructor for a color.
     pub fn rgb(r: u8, g: u8, b: u8) -> Color {
         Color { r, g, b }
     }
+
+    /// A convenience constructor for a gray color.
+    pub fn gray(value: u8) -> Color {
+        Color {
+            r: value,
+            g: value,
+            b: value,
+        }
+    }
+
+    /// The luminance of the color, using the Rec. 709 weights.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// assert_eq!(Color::WHITE.luminance(), 255);
+    /// assert_eq!(Color::rgb(0, 255, 0).luminance(), 182);
+    /// ```
+    pub fn luminance(&self) -> u8 {
+        self.luminance_with(Luma::Rec709)
+    }
+
+    /// The luminance of the color, using the weights of the given standard.
+    /// ```rust
+    /// # use pixel_canvas::{prelude::*, color::Luma};
+    /// assert_eq!(Color::rgb(0, 255, 0).luminance_with(Luma::Rec601), 150);
+    /// ```
+    pub fn luminance_with(&self, weights: Luma) -> u8 {
+        let (r, g, b) = weights.weights();
+        (self.r as f32 * r + self.g as f32 * g + self.b as f32 * b).round() as u8
+    }
+
+    /// Convert the color into linear light, using a [`GAMMA`] of 2.2.
+    ///
+    /// Blending colors in linear light and then converting back avoids the
+    /// darkening you get when blending sRGB colors directly.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mid = Color::BLACK.to_linear().blend(Color::WHITE.to_linear(), 0.5);
+    /// assert_eq!(Color::from_linear(mid), Color::gray(186));
+    /// ```
+    ///
+    /// [`GAMMA`]: constant.GAMMA.html
+    pub fn to_linear(&self) -> LinearColor {
+        let decode = |c: u8| (c as f32 / 255.0).powf(GAMMA);
+        LinearColor {
+            r: decode(self.r),
+            g: decode(self.g),
+            b: decode(self.b),
+        }
+    }
+
+    /// Convert a color from linear light, using a [`GAMMA`] of 2.2.
+    ///
+    /// Components outside of `0.0..=1.0` are clamped.
+    ///
+    /// [`GAMMA`]: constant.GAMMA.html
+    pub fn from_linear(color: LinearColor) -> Color {
+        let encode = |c: f32| (c.restrict(0.0..=1.0).powf(1.0 / GAMMA) * 255.0).round() as u8;
+        Color {
+            r: encode(color.r),
+            g: encode(color.g),
+            b: encode(color.b),
+        }
+    }
+
+    /// Convert a color to a vector, mapping each component from `0..=255` to
+    /// `0.0..=1.0`, in `x`, `y`, `z` order.
+    ///
+    /// Unlike [`to_linear`](#method.to_linear), no gamma is applied, the
+    /// components are just rescaled.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// assert_eq!(Color::rgb(255, 0, 51).to_vec3(), Vec3::xyz(1.0, 0.0, 0.2));
+    /// ```
+    pub fn to_vec3(&self) -> Vec3 {
+        Vec3::xyz(
+            self.r as f32 / 255.0,
+            self.g as f32 / 255.0,
+            self.b as f32 / 255.0,
+        )
+    }
+
+    /// Convert a vector to a color, mapping each component from `0.0..=1.0`
+    /// to `0..=255`.
+    ///
+    /// Components outside of `0.0..=1.0` are clamped.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let color = Color::from_vec3(Vec3::xyz(1.5, 0.5, -1.0));
+    /// assert_eq!(color, Color::rgb(255, 128, 0));
+    /// ```
+    pub fn from_vec3(v: Vec3) -> Color {
+        let encode = |c: f32| (c.restrict(0.0..=1.0) * 255.0).round() as u8;
+        Color {
+            r: encode(v.x),
+            g: encode(v.y),
+            b: encode(v.z),
+        }
+    }
+
+    /// The components of the color as an array, in red, green, blue order.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let color = Color::rgb(10, 20, 30);
+    /// assert_eq!(color.to_array(), [10, 20, 30]);
+    /// assert_eq!(Color::from([10, 20, 30]), color);
+    /// ```
+    pub fn to_array(&self) -> [u8; 3] {
+        [self.r, self.g, self.b]
+    }
+
+    /// The components of the color as an array of floats, mapped from
+    /// `0..=255` to `0.0..=1.0` like [`to_vec3`](#method.to_vec3).
+    ///
+    /// This is the layout shaders usually expect for a color.
+    pub fn to_array_f32(&self) -> [f32; 3] {
+        self.to_vec3().into()
+    }
+
+    /// The color in a palette that's nearest to this one.
+    ///
+    /// Distance is measured as the squared Euclidean distance between the
+    /// raw RGB values. This is simple and fast, but isn't perceptually
+    /// uniform: for example, differences in green are more visible than the
+    /// same differences in blue. Returns this color if the palette is empty.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let palette = [Color::BLACK, Color::rgb(255, 0, 0), Color::WHITE];
+    /// assert_eq!(Color::rgb(200, 30, 20).nearest(&palette), Color::rgb(255, 0, 0));
+    /// assert_eq!(Color::gray(100).nearest_index(&palette), Some(0));
+    /// ```
+    pub fn nearest(&self, palette: &[Color]) -> Color {
+        match self.nearest_index(palette) {
+            Some(i) => palette[i],
+            None => *self,
+        }
+    }
+
+    /// The index of the color in a palette that's nearest to this one, or
+    /// `None` if the palette is empty.
+    ///
+    /// See [`nearest`](#method.nearest) for how distance is measured.
+    pub fn nearest_index(&self, palette: &[Color]) -> Option<usize> {
+        let dist = |other: &Color| {
+            let dr = self.r as i32 - other.r as i32;
+            let dg = self.g as i32 - other.g as i32;
+            let db = self.b as i32 - other.b as i32;
+            dr * dr + dg * dg + db * db
+        };
+        (0..palette.len()).min_by_key(|&i| dist(&palette[i]))
+    }
+
+    /// Multiply two colors, darkening this one by the other.
+    ///
+    /// Unlike the `*` operator, this divides by exactly 255, so multiplying
+    /// by white leaves a color unchanged.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let c = Color::rgb(200, 100, 50);
+    /// assert_eq!(c.multiply(Color::WHITE), c);
+    /// assert_eq!(c.multiply(Color::gray(128)), Color::rgb(100, 50, 25));
+    /// ```
+    pub fn multiply(self, other: Color) -> Color {
+        self.zip(other, mul8)
+    }
+
+    /// Screen two colors, lightening this one by the other.
+    ///
+    /// This is the inverse of [`multiply`](#method.multiply): it multiplies
+    /// the inverted colors and inverts the result.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let c = Color::rgb(200, 100, 50);
+    /// assert_eq!(c.screen(Color::BLACK), c);
+    /// assert_eq!(c.screen(Color::WHITE), Color::WHITE);
+    /// ```
+    pub fn screen(self, other: Color) -> Color {
+        self.zip(other, |a, b| 255 - mul8(255 - a, 255 - b))
+    }
+
+    /// Overlay another color on top of this one.
+    ///
+    /// Where this color is dark the two are multiplied, and where it's
+    /// light they're screened, which increases contrast while keeping this
+    /// color's highlights and shadows.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let c = Color::rgb(200, 100, 50);
+    /// assert_eq!(c.overlay(Color::gray(128)), Color::rgb(200, 100, 50));
+    /// ```
+    pub fn overlay(self, other: Color) -> Color {
+        self.zip(other, |a, b| {
+            if a < 128 {
+                mul8(2 * a, b)
+            } else {
+                255 - mul8(2 * (255 - a), 255 - b)
+            }
+        })
+    }
+
+    /// Combine this color with another, using a [`BlendMode`].
+    ///
+    /// This color is the bottom layer, and `other` is the top layer.
+    /// ```rust
+    /// # use pixel_canvas::{prelude::*, color::BlendMode};
+    /// let (a, b) = (Color::rgb(200, 100, 50), Color::gray(100));
+    /// assert_eq!(a.blend_mode(b, BlendMode::Screen), a.screen(b));
+    /// assert_eq!(a.blend_mode(b, BlendMode::Add), a + b);
+    /// ```
+    ///
+    /// [`BlendMode`]: enum.BlendMode.html
+    pub fn blend_mode(self, other: Color, mode: BlendMode) -> Color {
+        match mode {
+            BlendMode::Normal => other,
+            BlendMode::Add => self + other,
+            BlendMode::Multiply => self.multiply(other),
+            BlendMode::Screen => self.screen(other),
+            BlendMode::Overlay => self.overlay(other),
+        }
+    }
+
+    /// Invert each channel of the color, like a photographic negative.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// assert_eq!(Color::rgb(255, 100, 0).invert(), Color::rgb(0, 155, 255));
+    /// ```
+    pub fn invert(self) -> Color {
+        Color {
+            r: 255 - self.r,
+            g: 255 - self.g,
+            b: 255 - self.b,
+        }
+    }
+
+    /// The average of several colors, or black if there are none.
+    ///
+    /// The channels are summed and divided once, so this doesn't drift like
+    /// a chain of 8-bit blends does. It's what you want for resolving
+    /// supersamples.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let samples = [Color::BLACK, Color::WHITE, Color::RED, Color::RED];
+    /// assert_eq!(Color::average(&samples), Color::rgb(191, 64, 64));
+    /// assert_eq!(Color::average(&[]), Color::BLACK);
+    /// ```
+    pub fn average(colors: &[Color]) -> Color {
+        let n = colors.len() as u32;
+        if n == 0 {
+            return Color::BLACK;
+        }
+        let mut sum = [0u32; 3];
+        for color in colors {
+            sum[0] += color.r as u32;
+            sum[1] += color.g as u32;
+            sum[2] += color.b as u32;
+        }
+        Color {
+            r: ((sum[0] + n / 2) / n) as u8,
+            g: ((sum[1] + n / 2) / n) as u8,
+            b: ((sum[2] + n / 2) / n) as u8,
+        }
+    }
+
+    /// The weighted average of several colors, or black if the weights don't
+    /// add up to more than zero.
+    ///
+    /// This is useful for resolving samples with a tent or gaussian filter.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let samples = [(Color::BLACK, 1.0), (Color::WHITE, 3.0)];
+    /// assert_eq!(Color::average_weighted(&samples), Color::gray(191));
+    /// ```
+    pub fn average_weighted(colors: &[(Color, f32)]) -> Color {
+        let total: f32 = colors.iter().map(|&(_, weight)| weight).sum();
+        if total <= 0.0 {
+            return Color::BLACK;
+        }
+        let mut sum = [0.0f32; 3];
+        for &(color, weight) in colors {
+            sum[0] += color.r as f32 * weight;
+            sum[1] += color.g as f32 * weight;
+            sum[2] += color.b as f32 * weight;
+        }
+        let channel = |sum: f32| (sum / total).round().restrict(0.0..=255.0) as u8;
+        Color {
+            r: channel(sum[0]),
+            g: channel(sum[1]),
+            b: channel(sum[2]),
+        }
+    }
+
+    fn zip(self, other: Color, f: impl Fn(u32, u32) -> u32) -> Color {
+        Color {
+            r: f(self.r as u32, other.r as u32) as u8,
+            g: f(self.g as u32, other.g as u32) as u8,
+            b: f(self.b as u32, other.b as u32) as u8,
+        }
+    }
+}
+
+/// A color in linear light, with components nominally in `0.0..=1.0`.
+///
+/// Create one with [`Color::to_linear`], and convert back with
+/// [`Color::from_linear`]. Components can go above `1.0` when accumulating
+/// light, use [`tone_map`] to bring them back into range.
+///
+/// [`Color::to_linear`]: struct.Color.html#method.to_linear
+/// [`Color::from_linear`]: struct.Color.html#method.from_linear
+/// [`tone_map`]: struct.LinearColor.html#method.tone_map
+// This must be repr(C) in order to directly upload to the GPU.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct LinearColor {
+    /// The red component.
+    pub r: f32,
+    /// The green component.
+    pub g: f32,
+    /// The blue component.
+    pub b: f32,
+}
+
+impl LinearColor {
+    /// A convenience constructor for a linear color.
+    pub fn rgb(r: f32, g: f32, b: f32) -> LinearColor {
+        LinearColor { r, g, b }
+    }
+
+    /// Compress an unbounded color into the `0.0..=1.0` range.
+    /// ```rust
+    /// # use pixel_canvas::color::{LinearColor, ToneMap};
+    /// let bright = LinearColor::rgb(3.0, 1.0, 0.0);
+    /// assert_eq!(bright.tone_map(ToneMap::Reinhard), LinearColor::rgb(0.75, 0.5, 0.0));
+    /// ```
+    pub fn tone_map(self, operator: ToneMap) -> LinearColor {
+        let map = |c: f32| match operator {
+            ToneMap::Clamp => c,
+            ToneMap::Reinhard => c / (1.0 + c),
+            ToneMap::Aces => (c * (2.51 * c + 0.03)) / (c * (2.43 * c + 0.59) + 0.14),
+        };
+        LinearColor {
+            r: map(self.r.max(0.0)).restrict(0.0..=1.0),
+            g: map(self.g.max(0.0)).restrict(0.0..=1.0),
+            b: map(self.b.max(0.0)).restrict(0.0..=1.0),
+        }
+    }
+}
+
+/// An operator to compress high dynamic range colors for display.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ToneMap {
+    /// Clip each channel to `1.0`. Bright colors lose detail and saturate.
+    Clamp,
+    /// The Reinhard operator, `c / (1 + c)`. Never clips, but can look flat.
+    Reinhard,
+    /// Krzysztof Narkowicz's fit of the ACES filmic curve. Gives a
+    /// contrasty, filmic look.
+    Aces,
+}
+
+impl Add<LinearColor> for LinearColor {
+    type Output = LinearColor;
+    fn add(self, rhs: LinearColor) -> LinearColor {
+        LinearColor {
+            r: self.r + rhs.r,
+            g: self.g + rhs.g,
+            b: self.b + rhs.b,
+        }
+    }
+}
+
+impl Mul<LinearColor> for LinearColor {
+    type Output = LinearColor;
+    fn mul(self, rhs: LinearColor) -> LinearColor {
+        LinearColor {
+            r: self.r * rhs.r,
+            g: self.g * rhs.g,
+            b: self.b * rhs.b,
+        }
+    }
+}
+
+impl Mul<f32> for LinearColor {
+    type Output = LinearColor;
+    fn mul(self, rhs: f32) -> LinearColor {
+        LinearColor {
+            r: self.r * rhs,
+            g: self.g * rhs,
+            b: self.b * rhs,
+        }
+    }
+}
+
+impl Blend<f32> for LinearColor {
+    fn blend(self, other: LinearColor, factor: f32) -> LinearColor {
+        LinearColor {
+            r: self.r * (1.0 - factor) + other.r * factor,
+            g: self.g * (1.0 - factor) + other.g * factor,
+            b: self.b * (1.0 - factor) + other.b * factor,
+        }
+    }
+}
+
+/// The standard weights used to compute the luminance of a color.
+///
+/// Rec. 601 is the older standard-definition television standard, and Rec.
+/// 709 is used for HDTV and sRGB. They give noticeably different results on
+/// saturated greens and blues.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Luma {
+    /// The Rec. 601 weights, (0.299, 0.587, 0.114).
+    Rec601,
+    /// The Rec. 709 weights, (0.2126, 0.7152, 0.0722).
+    Rec709,
+}
+
+impl Luma {
+    /// The red, green, and blue weights for this standard.
+    pub fn weights(self) -> (f32, f32, f32) {
+        match self {
+            Luma::Rec601 => (0.299, 0.587, 0.114),
+            Luma::Rec709 => (0.2126, 0.7152, 0.0722),
+        }
+    }
+}
+
+/// A way of combining two layers of color, as used by
+/// [`Color::blend_mode`].
+///
+/// [`Color::blend_mode`]: struct.Color.html#method.blend_mode
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BlendMode {
+    /// Replace the bottom layer with the top layer.
+    Normal,
+    /// Add the layers together, saturating at white.
+    Add,
+    /// Multiply the layers, which always darkens.
+    Multiply,
+    /// Screen the layers, which always lightens.
+    Screen,
+    /// Multiply dark areas and screen light areas of the bottom layer.
+    Overlay,
+}
+
+/// Multiply two 8-bit channel values, treating 255 as 1.0.
+fn mul8(a: u32, b: u32) -> u32 {
+    (a * b + 127) / 255
+}
+
+/// A multi-stop color ramp.
+///
+/// A gradient is built from a list of `(position, color)` stops, and can be
+/// sampled at any position. Sampling between two stops blends between their
+/// colors, and sampling outside of the stops gives the color of the nearest
+/// end.
+/// ```rust
+/// # use pixel_canvas::{prelude::*, color::Gradient};
+/// let heat = Gradient::new(vec![
+///     (0.0, Color::BLACK),
+///     (0.5, Color::rgb(255, 0, 0)),
+///     (1.0, Color::WHITE),
+/// ]);
+/// assert_eq!(heat.sample(-1.0), Color::BLACK);
+/// assert_eq!(heat.sample(0.25), Color::rgb(127, 0, 0));
+/// assert_eq!(heat.sample(0.5), Color::rgb(255, 0, 0));
+/// assert_eq!(heat.sample(2.0), Color::WHITE);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Gradient {
+    stops: Vec<(f32, Color)>,
+}
+
+impl Gradient {
+    /// Create a gradient from a list of stops.
+    ///
+    /// The stops don't need to be in order, they will be sorted by position.
+    pub fn new(stops: impl Into<Vec<(f32, Color)>>) -> Gradient {
+        let mut stops = stops.into();
+        stops.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        Gradient { stops }
+    }
+
+    /// The stops of the gradient, sorted by position.
+    pub fn stops(&self) -> &[(f32, Color)] {
+        &self.stops
+    }
+
+    /// Sample the color of the gradient at a given position.
+    ///
+    /// An empty gradient is always black.
+    pub fn sample(&self, t: f32) -> Color {
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Color::BLACK,
+        };
+        if t <= first.0 {
+            return first.1;
+        }
+        if t >= last.0 {
+            return last.1;
+        }
+        for pair in self.stops.windows(2) {
+            let ((start, from), (end, to)) = (pair[0], pair[1]);
+            if t <= end {
+                return from.blend(to, (t - start) / (end - start));
+            }
+        }
+        last.1
+    }
+}
+
+/// A list of up to 256 colors, for images that store palette indices.
+///
+/// See [`IndexedImage`](../image/struct.IndexedImage.html). Indices past the
+/// end of the palette are black.
+/// ```rust
+/// # use pixel_canvas::{prelude::*, color::Palette};
+/// let mut palette = Palette::new(vec![Color::BLACK, Color::RED]);
+/// assert_eq!(palette[1], Color::RED);
+/// palette[1] = Color::BLUE;
+/// assert_eq!(palette.get(1), Color::BLUE);
+/// assert_eq!(palette.get(200), Color::BLACK);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Palette {
+    colors: Vec<Color>,
+}
+
+impl Palette {
+    /// Create a palette from a list of colors.
+    ///
+    /// Panics if there are more than 256 colors, since they couldn't all be
+    /// indexed by a `u8`.
+    pub fn new(colors: impl Into<Vec<Color>>) -> Palette {
+        let colors = colors.into();
+        assert!(
+            colors.len() <= 256,
+            "a palette can have at most 256 colors, but got {}",
+            colors.len()
+        );
+        Palette { colors }
+    }
+
+    /// The colors of the palette.
+    pub fn colors(&self) -> &[Color] {
+        &self.colors
+    }
+
+    /// The colors of the palette, for changing them in place.
+    pub fn colors_mut(&mut self) -> &mut [Color] {
+        &mut self.colors
+    }
+
+    /// The color at an index, or black if it's past the end of the palette.
+    pub fn get(&self, index: u8) -> Color {
+        self.colors
+            .get(index as usize)
+            .copied()
+            .unwrap_or(Color::BLACK)
+    }
+
+    /// Cyclically shift the colors from `start` to `end`, inclusive, by `by`
+    /// places.
+    ///
+    /// Positive amounts move each color to a higher index, and the colors
+    /// shifted past `end` wrap around to `start`. Negative amounts shift the
+    /// other way. Shifting a range of a palette every frame animates an
+    /// indexed image without changing any of its pixels, the classic "color
+    /// cycling" effect used for waterfalls and fire.
+    ///
+    /// Panics if `start` is greater than `end`, or `end` is past the end of
+    /// the palette.
+    /// ```rust
+    /// # use pixel_canvas::{prelude::*, color::Palette};
+    /// let (a, b, c) = (Color::RED, Color::GREEN, Color::BLUE);
+    /// let mut palette = Palette::new(vec![Color::BLACK, a, b, c]);
+    /// palette.rotate(1, 3, 1);
+    /// assert_eq!(palette.colors(), &[Color::BLACK, c, a, b]);
+    /// palette.rotate(1, 3, -2);
+    /// assert_eq!(palette.colors(), &[Color::BLACK, b, c, a]);
+    /// ```
+    pub fn rotate(&mut self, start: usize, end: usize, by: i32) {
+        let range = &mut self.colors[start..=end];
+        let by = (by as i64).rem_euclid(range.len() as i64) as usize;
+        range.rotate_right(by);
+    }
+}
+
+impl Index<u8> for Palette {
+    type Output = Color;
+    fn index(&self, index: u8) -> &Color {
+        &self.colors[index as usize]
+    }
+}
+
+impl IndexMut<u8> for Palette {
+    fn index_mut(&mut self, index: u8) -> &mut Color {
+        &mut self.colors[index as usize]
+    }
+}
+
+/// A single RGBA-8888 color.
+///
+/// Colors are normally in straight (not premultiplied) alpha. The
+/// [`premultiply`] and [`unpremultiply`] methods convert between the two, and
+/// each method documents which it expects.
+///
+/// [`premultiply`]: #method.premultiply
+/// [`unpremultiply`]: #method.unpremultiply
+// This must be repr(C) in order to directly upload to the GPU.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rgba {
+    /// The red component.
+    pub r: u8,
+    /// The green component.
+    pub g: u8,
+    /// The blue component.
+    pub b: u8,
+    /// The alpha component, where 0 is transparent and 255 is opaque.
+    pub a: u8,
+}
+
+impl Rgba {
+    /// A convenience constructor for an RGBA color.
+    pub fn new(r: u8, g: u8, b: u8, a: u8) -> Rgba {
+        Rgba { r, g, b, a }
+    }
+
+    /// The color without its alpha component.
+    pub fn to_rgb(self) -> Color {
+        Color {
+            r: self.r,
+            g: self.g,
+            b: self.b,
+        }
+    }
+
+    /// Convert a straight alpha color into premultiplied alpha, by scaling
+    /// the color components by the alpha.
+    ///
+    /// The result should only be passed to functions expecting
+    /// premultiplied alpha, such as [`composite_over`].
+    /// ```rust
+    /// # use pixel_canvas::color::Rgba;
+    /// assert_eq!(Rgba::new(255, 100, 0, 128).premultiply(), Rgba::new(128, 50, 0, 128));
+    /// ```
+    ///
+    /// [`composite_over`]: #method.composite_over
+    pub fn premultiply(self) -> Rgba {
+        let a = self.a as u32;
+        Rgba {
+            r: mul8(self.r as u32, a) as u8,
+            g: mul8(self.g as u32, a) as u8,
+            b: mul8(self.b as u32, a) as u8,
+            a: self.a,
+        }
+    }
+
+    /// Convert a premultiplied alpha color back into straight alpha.
+    ///
+    /// Fully transparent colors become transparent black, since their color
+    /// information has been lost.
+    pub fn unpremultiply(self) -> Rgba {
+        if self.a == 0 {
+            return Rgba::default();
+        }
+        let a = self.a as u32;
+        let div = |c: u8| ((c as u32 * 255 + a / 2) / a).min(255) as u8;
+        Rgba {
+            r: div(self.r),
+            g: div(self.g),
+            b: div(self.b),
+            a: self.a,
+        }
+    }
+
+    /// Composite this color over a background with the Porter-Duff "over"
+    /// operator.
+    ///
+    /// Both colors and the result are in **premultiplied** alpha. Compositing
+    /// straight alpha colors with this will give the wrong result, so use
+    /// [`premultiply`] first. Working in premultiplied alpha avoids the dark
+    /// fringes that straight alpha blending leaves around the edges of
+    /// transparent sprites.
+    /// ```rust
+    /// # use pixel_canvas::{prelude::*, color::Rgba};
+    /// let sprite = Rgba::new(255, 0, 0, 128).premultiply();
+    /// let background = Rgba::from(Color::rgb(0, 0, 255));
+    /// let out = sprite.composite_over(background).unpremultiply();
+    /// assert_eq!(out, Rgba::new(128, 0, 127, 255));
+    /// ```
+    ///
+    /// [`premultiply`]: #method.premultiply
+    pub fn composite_over(self, background: Rgba) -> Rgba {
+        let inv = 255 - self.a as u32;
+        let over = |top: u8, bottom: u8| (top as u32 + mul8(bottom as u32, inv)).min(255) as u8;
+        Rgba {
+            r: over(self.r, background.r),
+            g: over(self.g, background.g),
+            b: over(self.b, background.b),
+            a: over(self.a, background.a),
+        }
+    }
+}
+
+impl From<[u8; 3]> for Color {
+    fn from([r, g, b]: [u8; 3]) -> Color {
+        Color { r, g, b }
+    }
+}
+
+impl From<Color> for [u8; 3] {
+    fn from(color: Color) -> [u8; 3] {
+        color.to_array()
+    }
+}
+
+impl From<Color> for Rgba {
+    /// Convert a color into an opaque RGBA color.
+    fn from(color: Color) -> Rgba {
+        Rgba {
+            r: color.r,
+            g: color.g,
+            b: color.b,
+            a: 255,
+        }
+    }
 }
 
 /// A trait to blend between two values by some factor.
@@ -128,3 +907,54 @@ impl Mul<f32> for Color {
         Color::BLACK.blend(self, rhs)
     }
 }
+
+impl Mul<Color> for u8 {
+    type Output = Color;
+    fn mul(self, rhs: Color) -> Color {
+        rhs * self
+    }
+}
+
+impl Mul<Color> for f32 {
+    type Output = Color;
+    fn mul(self, rhs: Color) -> Color {
+        rhs * self
+    }
+}
+
+/// Saturating addition in place, for accumulating light.
+/// ```rust
+/// # use pixel_canvas::prelude::*;
+/// let mut light = Color::rgb(200, 10, 0);
+/// light += Color::rgb(100, 10, 0);
+/// assert_eq!(light, Color::rgb(255, 20, 0));
+/// ```
+impl AddAssign<Color> for Color {
+    fn add_assign(&mut self, rhs: Color) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign<Color> for Color {
+    fn sub_assign(&mut self, rhs: Color) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign<Color> for Color {
+    fn mul_assign(&mut self, rhs: Color) {
+        *self = *self * rhs;
+    }
+}
+
+impl MulAssign<u8> for Color {
+    fn mul_assign(&mut self, rhs: u8) {
+        *self = *self * rhs;
+    }
+}
+
+impl MulAssign<f32> for Color {
+    fn mul_assign(&mut self, rhs: f32) {
+        *self = *self * rhs;
+    }
+}
@@ -0,0 +1,80 @@
+//! Helpers for drawing escape-time fractals, like the Mandelbrot and Julia
+//! sets.
+//!
+//! Escape-time fractals are drawn by repeatedly applying a function to each
+//! point, and coloring the point by how many iterations it takes to escape
+//! to infinity. With the `rayon` feature enabled, rows of the image are
+//! rendered in parallel.
+//! ```rust
+//! # use pixel_canvas::{prelude::*, color::Gradient, fractal};
+//! let mut image = Image::new(64, 48);
+//! let gradient = Gradient::new(vec![(0.0, Color::BLUE), (1.0, Color::BLACK)]);
+//! fractal::escape_time(&mut image, -2.0..1.0, -1.2..1.2, 64, &gradient, fractal::mandelbrot);
+//! // The origin is inside the Mandelbrot set.
+//! assert_eq!(image[XY(42, 24)], Color::BLACK);
+//! ```
+
+use crate::{
+    color::{Color, Gradient},
+    image::Image,
+    math::Remap,
+};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::ops::Range;
+
+/// Fill an image by counting the iterations of an escape-time function.
+///
+/// Each pixel is mapped to a point in the `x` and `y` ranges, and `iterate`
+/// is called with that point and `max_iterations`, returning the number of
+/// iterations it took to escape. Counts are colored by sampling the gradient
+/// at `count / max_iterations`, so points that never escape get the color at
+/// `1.0`.
+pub fn escape_time(
+    image: &mut Image,
+    x: Range<f32>,
+    y: Range<f32>,
+    max_iterations: u32,
+    gradient: &Gradient,
+    iterate: impl Fn((f32, f32), u32) -> u32 + Sync,
+) {
+    let (width, height) = (image.width(), image.height());
+    if width == 0 {
+        return;
+    }
+    let fill_row = |(row, pixels): (usize, &mut [Color])| {
+        let py = (row as f32).remap(0.0..height as f32, y.clone());
+        for (col, pix) in pixels.iter_mut().enumerate() {
+            let px = (col as f32).remap(0.0..width as f32, x.clone());
+            let count = iterate((px, py), max_iterations).min(max_iterations);
+            *pix = gradient.sample(count as f32 / max_iterations.max(1) as f32);
+        }
+    };
+    #[cfg(feature = "rayon")]
+    image.par_chunks_mut(width).enumerate().for_each(fill_row);
+    #[cfg(not(feature = "rayon"))]
+    image.chunks_mut(width).enumerate().for_each(fill_row);
+}
+
+/// The number of iterations for the point `z` to escape the Julia set with
+/// the constant `c`, up to `max_iterations`.
+///
+/// This iterates `z = z² + c` until `|z| > 2`.
+pub fn julia(z: (f32, f32), c: (f32, f32), max_iterations: u32) -> u32 {
+    let (mut re, mut im) = z;
+    for i in 0..max_iterations {
+        if re * re + im * im > 4.0 {
+            return i;
+        }
+        let next_re = re * re - im * im + c.0;
+        im = 2.0 * re * im + c.1;
+        re = next_re;
+    }
+    max_iterations
+}
+
+/// The number of iterations for the point `c` to escape the Mandelbrot set,
+/// up to `max_iterations`.
+pub fn mandelbrot(c: (f32, f32), max_iterations: u32) -> u32 {
+    julia((0.0, 0.0), c, max_iterations)
+}
@@ -5,14 +5,26 @@
 //!
 //! [`Image`]: struct.Image.html
 
-// @Todo: Add multiple pixel formats?
 // @Todo: Seaparate stride from width, and document.
 
-use crate::color::Color;
-use glium::texture::{ClientFormat, RawImage2d, Texture2dDataSource};
+use crate::{
+    color::{Blend, Color, LinearColor, Luma, Palette, Rgba, ToneMap},
+    font,
+    math::Restrict,
+    vector::Vec2,
+};
+use glium::{
+    texture::{ClientFormat, PixelValue, RawImage2d, Texture2dDataSource, UncompressedFloatFormat},
+    Rect,
+};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 use std::{
     borrow::Cow,
-    ops::{Deref, DerefMut, Index, IndexMut},
+    error::Error,
+    fmt,
+    io::{self, Read, Write},
+    ops::{Add, Deref, DerefMut, Index, IndexMut},
 };
 
 /// An image for editing.
@@ -21,22 +33,268 @@ use std::{
 /// pixels via regular (mutable) slice methods. In addition, you can index
 /// into the image by `(row, column)` pairs.
 ///
+/// Images are RGB by default, but can store any [`Pixel`] format, like
+/// [`Rgba`] or grayscale `u8` (see [`GrayImage`]).
+///
 /// [`Color`]: ../color/struct.Color.html
-pub struct Image {
+/// [`Pixel`]: trait.Pixel.html
+/// [`Rgba`]: ../color/struct.Rgba.html
+/// [`GrayImage`]: type.GrayImage.html
+#[derive(Clone)]
+pub struct Image<P = Color> {
     width: usize,
     height: usize,
-    pixels: Vec<Color>,
+    pixels: Vec<P>,
+    dirty: Vec<Rect>,
+}
+
+/// A single channel image, useful for things like heightfields and masks.
+///
+/// It's displayed as shades of gray.
+pub type GrayImage = Image<u8>;
+
+/// A high precision image in linear light, for accumulating HDR lighting.
+///
+/// Render into it, and then [`tone_map`] it to get a regular image for
+/// display.
+///
+/// [`tone_map`]: struct.Image.html#method.tone_map
+pub type FloatImage = Image<LinearColor>;
+
+/// How [`Image::normalize_with`] stretches the range of the image.
+///
+/// [`Image::normalize_with`]: struct.Image.html#method.normalize_with
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Normalize {
+    /// Stretch each channel separately. This gives the most contrast, but
+    /// also corrects color casts, which changes the hues of the image.
+    PerChannel,
+    /// Stretch every channel by the same amount, based on the luminance.
+    /// This keeps the balance between the channels.
+    Luminance,
+}
+
+/// A format of pixel that can be stored in an [`Image`] and displayed on a
+/// canvas.
+///
+/// [`Image`]: struct.Image.html
+pub trait Pixel: Copy + Default + Send + Sync + 'static {
+    /// The type of each pixel of the data uploaded to the GPU.
+    type Texel: PixelValue;
+
+    /// The format of the texture used to display images of this format.
+    const TEXTURE_FORMAT: UncompressedFloatFormat;
+
+    /// Convert a slice of pixels into the data uploaded to the GPU.
+    fn upload(pixels: &[Self]) -> Cow<'_, [Self::Texel]>;
+
+    /// Convert from an RGB color, for example when clearing an image.
+    fn from_color(color: Color) -> Self;
+
+    /// The average of a non-empty slice of pixels, used when downsampling.
+    fn average(pixels: &[Self]) -> Self;
+}
+
+// Safe because these are all `repr(C)` and made of exactly the channels of
+// their client format.
+unsafe impl PixelValue for Color {
+    fn get_format() -> ClientFormat {
+        ClientFormat::U8U8U8
+    }
+}
+
+unsafe impl PixelValue for Rgba {
+    fn get_format() -> ClientFormat {
+        ClientFormat::U8U8U8U8
+    }
+}
+
+unsafe impl PixelValue for LinearColor {
+    fn get_format() -> ClientFormat {
+        ClientFormat::F32F32F32
+    }
+}
+
+impl Pixel for Color {
+    type Texel = Color;
+    const TEXTURE_FORMAT: UncompressedFloatFormat = UncompressedFloatFormat::U8U8U8;
+
+    fn upload(pixels: &[Color]) -> Cow<'_, [Color]> {
+        Cow::Borrowed(pixels)
+    }
+
+    fn from_color(color: Color) -> Color {
+        color
+    }
+
+    fn average(pixels: &[Color]) -> Color {
+        Color::average(pixels)
+    }
+}
+
+impl Pixel for Rgba {
+    type Texel = Rgba;
+    const TEXTURE_FORMAT: UncompressedFloatFormat = UncompressedFloatFormat::U8U8U8U8;
+
+    fn upload(pixels: &[Rgba]) -> Cow<'_, [Rgba]> {
+        Cow::Borrowed(pixels)
+    }
+
+    fn from_color(color: Color) -> Rgba {
+        Rgba::from(color)
+    }
+
+    fn average(pixels: &[Rgba]) -> Rgba {
+        let n = pixels.len() as u32;
+        let mut sum = [0u32; 4];
+        for pix in pixels {
+            sum[0] += pix.r as u32;
+            sum[1] += pix.g as u32;
+            sum[2] += pix.b as u32;
+            sum[3] += pix.a as u32;
+        }
+        Rgba {
+            r: ((sum[0] + n / 2) / n) as u8,
+            g: ((sum[1] + n / 2) / n) as u8,
+            b: ((sum[2] + n / 2) / n) as u8,
+            a: ((sum[3] + n / 2) / n) as u8,
+        }
+    }
+}
+
+impl Pixel for u8 {
+    type Texel = Color;
+    const TEXTURE_FORMAT: UncompressedFloatFormat = UncompressedFloatFormat::U8U8U8;
+
+    fn upload(pixels: &[u8]) -> Cow<'_, [Color]> {
+        // A single channel texture would display as red, so expand to RGB.
+        let colors = pixels.iter().map(|&value| Color::rgb(value, value, value));
+        Cow::Owned(colors.collect())
+    }
+
+    fn from_color(color: Color) -> u8 {
+        color.luminance()
+    }
+
+    fn average(pixels: &[u8]) -> u8 {
+        let n = pixels.len() as u32;
+        let sum: u32 = pixels.iter().map(|&value| value as u32).sum();
+        ((sum + n / 2) / n) as u8
+    }
+}
+
+/// The magic bytes at the start of a raw image.
+const RAW_MAGIC: &[u8; 4] = b"PXCV";
+
+/// Fill a slice with copies of one value.
+fn fill_slice<P: Copy>(slice: &mut [P], value: P) {
+    let len = slice.len();
+    if len == 0 {
+        return;
+    }
+    // Copy the filled prefix onto the rest of the slice, doubling it each
+    // time, so that most of the work is done by large `memcpy`s.
+    slice[0] = value;
+    let mut filled = 1;
+    while filled < len {
+        let count = filled.min(len - filled);
+        let (done, rest) = slice.split_at_mut(filled);
+        rest[..count].copy_from_slice(&done[..count]);
+        filled += count;
+    }
 }
 
+/// The offsets of the Moore neighborhood, in the order returned by
+/// `Image::neighbors`.
+const NEIGHBOR_OFFSETS: [(i64, i64); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
 /// A row/column pair for indexing into an image.
 /// Distinct from an x/y pair.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct RC(pub usize, pub usize);
 
 /// An x/y pair for indexing into an image.
 /// Distinct from a row/column pair.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct XY(pub usize, pub usize);
 
-impl Image {
+impl XY {
+    /// Offset the position, or `None` if either coordinate would go below
+    /// zero.
+    ///
+    /// This is handy for stepping to neighbors without wrapping around.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// assert_eq!(XY(3, 0).checked_add(-1, 1), Some(XY(2, 1)));
+    /// assert_eq!(XY(3, 0).checked_add(0, -1), None);
+    /// ```
+    pub fn checked_add(self, dx: i32, dy: i32) -> Option<XY> {
+        let x = (self.0 as i64).checked_add(dx as i64)?;
+        let y = (self.1 as i64).checked_add(dy as i64)?;
+        if x < 0 || y < 0 {
+            return None;
+        }
+        Some(XY(x as usize, y as usize))
+    }
+}
+
+/// Add two positions component-wise.
+/// ```rust
+/// # use pixel_canvas::prelude::*;
+/// let mut image = Image::new(4, 4);
+/// let pos = XY(1, 2);
+/// image[pos + XY(1, 0)] = Color::WHITE;
+/// assert_eq!(image[XY(2, 2)], Color::WHITE);
+/// ```
+impl Add for XY {
+    type Output = XY;
+    fn add(self, rhs: XY) -> XY {
+        XY(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+
+impl From<(usize, usize)> for XY {
+    fn from((x, y): (usize, usize)) -> XY {
+        XY(x, y)
+    }
+}
+
+impl From<(usize, usize)> for RC {
+    fn from((row, column): (usize, usize)) -> RC {
+        RC(row, column)
+    }
+}
+
+impl Pixel for LinearColor {
+    type Texel = LinearColor;
+    const TEXTURE_FORMAT: UncompressedFloatFormat = UncompressedFloatFormat::F32F32F32;
+
+    fn upload(pixels: &[LinearColor]) -> Cow<'_, [LinearColor]> {
+        Cow::Borrowed(pixels)
+    }
+
+    fn from_color(color: Color) -> LinearColor {
+        color.to_linear()
+    }
+
+    fn average(pixels: &[LinearColor]) -> LinearColor {
+        let sum = pixels
+            .iter()
+            .fold(LinearColor::default(), |sum, &pix| sum + pix);
+        sum * (1.0 / pixels.len() as f32)
+    }
+}
+
+impl<P: Pixel> Image<P> {
     /// The width of the image in pixels.
     pub fn width(&self) -> usize {
         self.width
@@ -47,72 +305,1649 @@ impl Image {
         self.height
     }
 
-    /// Create an all-black image with the given dimensions.
-    pub fn new(width: usize, height: usize) -> Image {
+    /// Create an image with the given dimensions, filled with one pixel
+    /// value.
+    /// ```rust
+    /// # use pixel_canvas::{prelude::*, image::GrayImage};
+    /// let heights = GrayImage::filled(16, 16, 128);
+    /// assert_eq!(heights[XY(0, 0)], 128);
+    /// ```
+    pub fn filled(width: usize, height: usize, pixel: P) -> Image<P> {
         Image {
             width,
             height,
-            pixels: vec![Color { r: 0, g: 0, b: 0 }; (width * height) as usize],
+            pixels: vec![pixel; width * height],
+            dirty: Vec::new(),
+        }
+    }
+
+    /// Create an image from existing pixel data, without copying it.
+    ///
+    /// The pixels are in row-major order, starting from the bottom row. This
+    /// fails if the number of pixels doesn't match the dimensions.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let image = Image::from_vec(2, 1, vec![Color::BLACK, Color::WHITE]).unwrap();
+    /// assert_eq!(image[XY(1, 0)], Color::WHITE);
+    /// assert!(Image::from_vec(2, 2, vec![Color::BLACK]).is_err());
+    /// ```
+    pub fn from_vec(
+        width: usize,
+        height: usize,
+        pixels: Vec<P>,
+    ) -> Result<Image<P>, DimensionError> {
+        if pixels.len() != width * height {
+            return Err(DimensionError {
+                width,
+                height,
+                len: pixels.len(),
+            });
+        }
+        Ok(Image {
+            width,
+            height,
+            pixels,
+            dirty: Vec::new(),
+        })
+    }
+
+    /// Create an image by calling a function with the x/y position of each
+    /// pixel.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let image = Image::from_fn(256, 1, |x, _| Color::gray(x as u8));
+    /// assert_eq!(image[XY(128, 0)], Color::gray(128));
+    /// ```
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> P) -> Image<P> {
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                pixels.push(f(x, y));
+            }
+        }
+        Image {
+            width,
+            height,
+            pixels,
+            dirty: Vec::new(),
+        }
+    }
+
+    /// Get the pixel at an x/y position, or `None` if it's out of bounds.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let image = Image::new(4, 4);
+    /// assert_eq!(image.get(XY(3, 3)), Some(&Color::BLACK));
+    /// assert_eq!(image.get(XY(4, 0)), None);
+    /// ```
+    pub fn get(&self, XY(x, y): XY) -> Option<&P> {
+        if x < self.width && y < self.height {
+            Some(&self.pixels[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    /// Mutably get the pixel at an x/y position, or `None` if it's out of
+    /// bounds.
+    pub fn get_mut(&mut self, XY(x, y): XY) -> Option<&mut P> {
+        if x < self.width && y < self.height {
+            Some(&mut self.pixels[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    /// Set many pixels at once, skipping any points that are out of bounds
+    /// instead of panicking like indexing does.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::new(4, 4);
+    /// image.set_pixels(vec![(XY(1, 2), Color::WHITE), (XY(9, 0), Color::RED)]);
+    /// assert_eq!(image[XY(1, 2)], Color::WHITE);
+    /// ```
+    pub fn set_pixels(&mut self, points: impl IntoIterator<Item = (XY, P)>) {
+        for (pos, pixel) in points {
+            if let Some(pix) = self.get_mut(pos) {
+                *pix = pixel;
+            }
         }
     }
 
     /// Fill the image with a single solid color.
-    pub fn fill(&mut self, color: Color) {
+    ///
+    /// With the `rayon` feature enabled, rows are filled in parallel.
+    pub fn fill(&mut self, color: P) {
+        #[cfg(feature = "rayon")]
+        self.pixels
+            .par_chunks_mut(self.width.max(1))
+            .for_each(|row| fill_slice(row, color));
+        #[cfg(not(feature = "rayon"))]
+        fill_slice(&mut self.pixels, color);
+    }
+
+    /// Fill a rectangle of the image with a single solid color, starting at
+    /// its lower-left corner.
+    ///
+    /// The rectangle is clipped to the image. With the `rayon` feature
+    /// enabled, rows are filled in parallel.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::new(8, 8);
+    /// image.fill_rect(XY(6, 2), 4, 3, Color::RED);
+    /// assert_eq!(image[XY(7, 4)], Color::RED);
+    /// assert_eq!(image[XY(5, 4)], Color::BLACK);
+    /// assert_eq!(image[XY(7, 5)], Color::BLACK);
+    /// ```
+    pub fn fill_rect(&mut self, XY(x, y): XY, width: usize, height: usize, color: P) {
+        let right = x.saturating_add(width).min(self.width);
+        let top = y.saturating_add(height).min(self.height);
+        if x >= right || y >= top {
+            return;
+        }
+        let stride = self.width;
+        let rows = &mut self.pixels[y * stride..top * stride];
+        let fill_row = |row: &mut [P]| fill_slice(&mut row[x..right], color);
+        #[cfg(feature = "rayon")]
+        rows.par_chunks_mut(stride).for_each(fill_row);
+        #[cfg(not(feature = "rayon"))]
+        rows.chunks_mut(stride).for_each(fill_row);
+    }
+
+    /// Get the pixel at an x/y position, wrapping around the edges.
+    ///
+    /// Coordinates past one edge wrap around to the opposite edge, so `-1`
+    /// is the last row or column. This makes tiling patterns and toroidal
+    /// cellular automata easy.
+    ///
+    /// Panics if the image is empty.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::new(4, 3);
+    /// image[XY(3, 2)] = Color::WHITE;
+    /// assert_eq!(*image.wrap(-1, -1), Color::WHITE);
+    /// assert_eq!(*image.wrap(7, 5), Color::WHITE);
+    /// ```
+    pub fn wrap(&self, x: i32, y: i32) -> &P {
+        let i = self.wrap_index(x, y);
+        &self.pixels[i]
+    }
+
+    /// Mutably get the pixel at an x/y position, wrapping around the edges.
+    ///
+    /// See [`wrap`](#method.wrap) for details.
+    pub fn wrap_mut(&mut self, x: i32, y: i32) -> &mut P {
+        let i = self.wrap_index(x, y);
+        &mut self.pixels[i]
+    }
+
+    /// The 8 pixels surrounding an x/y position (its Moore neighborhood), with
+    /// `None` for neighbors that are out of bounds.
+    ///
+    /// The neighbors are ordered row by row, from the bottom left to the top
+    /// right, skipping the center: `(x-1, y-1)`, `(x, y-1)`, `(x+1, y-1)`,
+    /// `(x-1, y)`, `(x+1, y)`, `(x-1, y+1)`, `(x, y+1)`, `(x+1, y+1)`.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::new(3, 3);
+    /// image[XY(1, 0)] = Color::WHITE;
+    /// let neighbors = image.neighbors(XY(0, 0));
+    /// assert_eq!(neighbors[0], None);
+    /// assert_eq!(neighbors[4], Some(Color::WHITE));
+    /// ```
+    pub fn neighbors(&self, XY(x, y): XY) -> [Option<P>; 8] {
+        let mut out = [None; 8];
+        for (slot, &(dx, dy)) in out.iter_mut().zip(&NEIGHBOR_OFFSETS) {
+            let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+            if nx >= 0 && ny >= 0 && (nx as usize) < self.width && (ny as usize) < self.height {
+                *slot = Some(self.pixels[ny as usize * self.width + nx as usize]);
+            }
+        }
+        out
+    }
+
+    /// The 8 pixels surrounding an x/y position, wrapping around the edges of
+    /// the image like a torus.
+    ///
+    /// The neighbors are in the same order as [`neighbors`](#method.neighbors).
+    /// This is the usual choice for cellular automata, since there are no
+    /// edges where the rules behave differently.
+    pub fn neighbors_wrap(&self, XY(x, y): XY) -> [P; 8] {
+        let mut out = [P::default(); 8];
+        for (slot, &(dx, dy)) in out.iter_mut().zip(&NEIGHBOR_OFFSETS) {
+            *slot = *self.wrap((x as i64 + dx) as i32, (y as i64 + dy) as i32);
+        }
+        out
+    }
+
+    fn wrap_index(&self, x: i32, y: i32) -> usize {
+        let x = (x as i64).rem_euclid(self.width as i64) as usize;
+        let y = (y as i64).rem_euclid(self.height as i64) as usize;
+        y * self.width + x
+    }
+
+    /// Shrink the image by an integer factor, averaging each `factor` by
+    /// `factor` block of pixels into one.
+    ///
+    /// Pixels at the right and top edges that don't make up a full block are
+    /// dropped.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let image = Image::from_fn(4, 2, |x, _| Color::gray(if x % 2 == 0 { 0 } else { 200 }));
+    /// let small = image.downsample(2);
+    /// assert_eq!((small.width(), small.height()), (2, 1));
+    /// assert!(small.iter().all(|&pix| pix == Color::gray(100)));
+    /// ```
+    pub fn downsample(&self, factor: usize) -> Image<P> {
+        assert!(factor > 0, "can't downsample by a factor of 0");
+        let (width, height) = (self.width / factor, self.height / factor);
+        let mut block = Vec::with_capacity(factor * factor);
+        Image::from_fn(width, height, |x, y| {
+            block.clear();
+            for row in y * factor..(y + 1) * factor {
+                let start = row * self.width + x * factor;
+                block.extend_from_slice(&self.pixels[start..start + factor]);
+            }
+            P::average(&block)
+        })
+    }
+
+    /// Create a new image by applying a function to every pixel of this one.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let image = Image::filled(4, 4, Color::rgb(10, 20, 30));
+    /// let brighter = image.map(|pix| pix + Color::gray(100));
+    /// assert_eq!(brighter[XY(0, 0)], Color::rgb(110, 120, 130));
+    /// ```
+    pub fn map(&self, f: impl Fn(P) -> P) -> Image<P> {
+        Image {
+            width: self.width,
+            height: self.height,
+            pixels: self.pixels.iter().map(|&pix| f(pix)).collect(),
+            dirty: Vec::new(),
+        }
+    }
+
+    /// Create a new image by applying a function to the x/y position and
+    /// value of every pixel of this one.
+    ///
+    /// This is handy for position dependent effects, like vignettes.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let image = Image::filled(4, 1, Color::WHITE);
+    /// let faded = image.map_with_coords(|x, _, pix| pix * (x as f32 / 3.0));
+    /// assert_eq!(faded[XY(0, 0)], Color::BLACK);
+    /// assert_eq!(faded[XY(3, 0)], Color::WHITE);
+    /// ```
+    pub fn map_with_coords(&self, f: impl Fn(usize, usize, P) -> P) -> Image<P> {
+        Image::from_fn(self.width, self.height, |x, y| {
+            f(x, y, self.pixels[y * self.width + x])
+        })
+    }
+
+    /// Iterate over the rows of the image, from the bottom row up.
+    pub fn rows(&self) -> std::slice::Chunks<'_, P> {
+        self.pixels.chunks(self.width.max(1))
+    }
+
+    /// Iterate mutably over the rows of the image, from the bottom row up.
+    pub fn rows_mut(&mut self) -> std::slice::ChunksMut<'_, P> {
+        self.pixels.chunks_mut(self.width.max(1))
+    }
+
+    /// Iterate mutably over the rows of the image along with their `y`
+    /// coordinate.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::new(4, 3);
+    /// for (y, row) in image.rows_mut_enumerated() {
+    ///     for (x, pixel) in row.iter_mut().enumerate() {
+    ///         *pixel = Color::rgb(x as u8, y as u8, 0);
+    ///     }
+    /// }
+    /// assert_eq!(image[XY(3, 2)], Color::rgb(3, 2, 0));
+    /// ```
+    pub fn rows_mut_enumerated(&mut self) -> std::iter::Enumerate<std::slice::ChunksMut<'_, P>> {
+        self.rows_mut().enumerate()
+    }
+
+    /// Iterate mutably over the pixels of the image in parallel, along with
+    /// their x/y position.
+    ///
+    /// This requires the `rayon` feature.
+    /// ```rust
+    /// # #[cfg(feature = "rayon")]
+    /// # {
+    /// # use pixel_canvas::prelude::*;
+    /// use rayon::prelude::*;
+    ///
+    /// let mut image = Image::new(4, 3);
+    /// image
+    ///     .par_enumerate_pixels_mut()
+    ///     .for_each(|(XY(x, y), pixel)| *pixel = Color::rgb(x as u8, y as u8, 0));
+    /// assert_eq!(image[XY(3, 2)], Color::rgb(3, 2, 0));
+    /// # }
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_enumerate_pixels_mut(
+        &mut self,
+    ) -> impl IndexedParallelIterator<Item = (XY, &mut P)> + '_ {
+        let width = self.width.max(1);
+        self.pixels
+            .par_iter_mut()
+            .enumerate()
+            .map(move |(i, pixel)| (XY(i % width, i / width), pixel))
+    }
+
+    /// Borrow a rectangle of the image, starting at its lower-left corner, to
+    /// edit on its own.
+    ///
+    /// Panics if the rectangle doesn't fit inside the image.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::new(8, 8);
+    /// let mut view = image.sub_image_mut(XY(2, 3), 4, 2);
+    /// view.fill(Color::RED);
+    /// view[XY(0, 0)] = Color::WHITE;
+    /// assert_eq!(image[XY(2, 3)], Color::WHITE);
+    /// assert_eq!(image[XY(5, 4)], Color::RED);
+    /// assert_eq!(image[XY(6, 4)], Color::BLACK);
+    /// ```
+    pub fn sub_image_mut(
+        &mut self,
+        XY(x, y): XY,
+        width: usize,
+        height: usize,
+    ) -> ImageViewMut<'_, P> {
+        assert!(
+            x + width <= self.width && y + height <= self.height,
+            "a {}x{} rectangle at ({}, {}) doesn't fit in a {}x{} image",
+            width,
+            height,
+            x,
+            y,
+            self.width,
+            self.height
+        );
+        let stride = self.width.max(1);
+        let rows = self.pixels.chunks_mut(stride).skip(y).take(height);
+        ImageViewMut {
+            origin: XY(x, y),
+            width,
+            rows: rows.map(|row| &mut row[x..x + width]).collect(),
+        }
+    }
+
+    /// Split the image into tiles that can be edited independently, for
+    /// example on different threads.
+    ///
+    /// The tiles are in rows from the lower-left corner. Tiles at the right
+    /// and top edges are smaller if the image doesn't divide evenly.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::new(10, 6);
+    /// let mut tiles = image.tiles_mut(4, 4);
+    /// assert_eq!(tiles.len(), 6);
+    /// assert_eq!((tiles[2].width(), tiles[2].height()), (2, 4));
+    /// for tile in &mut tiles {
+    ///     // Each tile could be sent to a different thread.
+    ///     tile.fill(Color::WHITE);
+    /// }
+    /// drop(tiles);
+    /// assert!(image.iter().all(|&pix| pix == Color::WHITE));
+    /// ```
+    pub fn tiles_mut(&mut self, tile_width: usize, tile_height: usize) -> Vec<ImageViewMut<'_, P>> {
+        assert!(
+            tile_width > 0 && tile_height > 0,
+            "tiles must be at least one pixel"
+        );
+        let (width, height) = (self.width, self.height);
+        let columns = (0..width).step_by(tile_width).len();
+        let mut tiles = Vec::new();
+        for (y, row) in self.pixels.chunks_mut(width.max(1)).enumerate() {
+            if y % tile_height == 0 {
+                let tile_rows = tile_height.min(height - y);
+                for column in 0..columns {
+                    let x = column * tile_width;
+                    tiles.push(ImageViewMut {
+                        origin: XY(x, y),
+                        width: tile_width.min(width - x),
+                        rows: Vec::with_capacity(tile_rows),
+                    });
+                }
+            }
+            // Hand each tile in this band its own part of the row.
+            let band = tiles.len() - columns;
+            let mut rest = row;
+            for tile in &mut tiles[band..] {
+                let (part, remainder) = rest.split_at_mut(tile.width);
+                tile.rows.push(part);
+                rest = remainder;
+            }
+        }
+        tiles
+    }
+
+    /// Count the pixels that differ between two images, or `None` if their
+    /// dimensions don't match.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let a = Image::new(4, 4);
+    /// let mut b = a.clone();
+    /// assert_eq!(a.diff(&b), Some(0));
+    /// b[XY(1, 2)] = Color::WHITE;
+    /// assert_eq!(a.diff(&b), Some(1));
+    /// assert_eq!(a.diff(&Image::new(2, 2)), None);
+    /// ```
+    pub fn diff(&self, other: &Image<P>) -> Option<usize>
+    where
+        P: PartialEq,
+    {
+        if (self.width, self.height) != (other.width, other.height) {
+            return None;
+        }
+        let differing = self.pixels.iter().zip(&other.pixels);
+        Some(differing.filter(|(a, b)| a != b).count())
+    }
+
+    /// Mark a rectangle of the image as changed this frame, starting at its
+    /// lower-left corner.
+    ///
+    /// When a render callback marks any dirty rectangles, the canvas only
+    /// uploads those regions to the GPU instead of the whole image, which is
+    /// much faster when only a small part of a large image changes. Every
+    /// pixel you change needs to be inside a marked rectangle, or it won't
+    /// show up. If nothing is marked, the whole image is uploaded as usual.
+    /// The marks are cleared after every frame.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::new(64, 64);
+    /// for y in 10..20 {
+    ///     for x in 30..40 {
+    ///         image[XY(x, y)] = Color::WHITE;
+    ///     }
+    /// }
+    /// image.mark_dirty(XY(30, 10), 10, 10);
+    /// ```
+    pub fn mark_dirty(&mut self, XY(x, y): XY, width: usize, height: usize) {
+        let right = x.saturating_add(width).min(self.width);
+        let top = y.saturating_add(height).min(self.height);
+        if x < right && y < top {
+            self.dirty.push(Rect {
+                left: x as u32,
+                bottom: y as u32,
+                width: (right - x) as u32,
+                height: (top - y) as u32,
+            });
+        }
+    }
+
+    /// Take the rectangles marked dirty since the last call.
+    pub(crate) fn take_dirty(&mut self) -> Vec<Rect> {
+        std::mem::take(&mut self.dirty)
+    }
+
+    /// Copy a rectangle of the image into data to upload to the GPU.
+    pub(crate) fn region(&self, rect: &Rect) -> RawImage2d<'static, P::Texel> {
+        let (left, bottom) = (rect.left as usize, rect.bottom as usize);
+        let (width, height) = (rect.width as usize, rect.height as usize);
+        let mut pixels = Vec::with_capacity(width * height);
+        for row in bottom..bottom + height {
+            let start = row * self.width + left;
+            pixels.extend_from_slice(&self.pixels[start..start + width]);
+        }
+        RawImage2d {
+            data: Cow::Owned(P::upload(&pixels).into_owned()),
+            width: rect.width,
+            height: rect.height,
+            format: P::Texel::get_format(),
+        }
+    }
+}
+
+impl Image {
+    /// Create an all-black image with the given dimensions.
+    pub fn new(width: usize, height: usize) -> Image {
+        Image::filled(width, height, Color::BLACK)
+    }
+
+    /// The raw bytes of the image.
+    ///
+    /// Each pixel is three bytes in RGB order (RGB-888), and the rows are
+    /// packed together with no padding, running from the bottom row up, like
+    /// [`rows`](#method.rows). Tools that expect top-down data, like
+    /// `ffmpeg -f rawvideo -pix_fmt rgb24`, need to flip it vertically, for
+    /// example with `-vf vflip`.
+    pub fn as_bytes(&self) -> &[u8] {
+        // Safe because `Color` is `repr(C)` and made of exactly three `u8`s.
+        unsafe {
+            std::slice::from_raw_parts(self.pixels.as_ptr() as *const u8, self.pixels.len() * 3)
+        }
+    }
+
+    /// The raw bytes of the image, mutably.
+    ///
+    /// See [`as_bytes`](#method.as_bytes) for the layout.
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        // Safe because `Color` is `repr(C)` and made of exactly three `u8`s,
+        // so every byte pattern is a valid color.
+        unsafe {
+            std::slice::from_raw_parts_mut(
+                self.pixels.as_mut_ptr() as *mut u8,
+                self.pixels.len() * 3,
+            )
+        }
+    }
+
+    /// Write the image in a simple uncompressed format, for caching renders.
+    ///
+    /// The format is the magic bytes `PXCV`, the width and height as
+    /// little-endian `u32`s, and then the [`as_bytes`](#method.as_bytes) of
+    /// the image. It's much faster to write and read than an encoded format
+    /// like PNG, but the files are large. Read it back with
+    /// [`read_raw`](#method.read_raw).
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::new(3, 2);
+    /// image[XY(2, 1)] = Color::rgb(1, 2, 3);
+    /// let mut data = Vec::new();
+    /// image.write_raw(&mut data).unwrap();
+    /// assert_eq!(data.len(), 12 + 3 * 2 * 3);
+    /// assert!(Image::read_raw(&data[..]).unwrap() == image);
+    /// assert!(Image::read_raw(&data[..20]).is_err());
+    /// // A header claiming a huge image fails without allocating it.
+    /// let huge = [&data[..4], &[255, 255, 0, 0, 255, 255, 0, 0]].concat();
+    /// assert!(Image::read_raw(&huge[..]).is_err());
+    /// ```
+    pub fn write_raw(&self, mut w: impl Write) -> io::Result<()> {
+        w.write_all(RAW_MAGIC)?;
+        w.write_all(&(self.width as u32).to_le_bytes())?;
+        w.write_all(&(self.height as u32).to_le_bytes())?;
+        w.write_all(self.as_bytes())
+    }
+
+    /// Read an image written by [`write_raw`](#method.write_raw).
+    ///
+    /// This fails with `ErrorKind::InvalidData` if the header is wrong, and
+    /// `ErrorKind::UnexpectedEof` if the data is truncated.
+    pub fn read_raw(mut r: impl Read) -> io::Result<Image> {
+        let mut header = [0u8; 12];
+        r.read_exact(&mut header)?;
+        if &header[..4] != RAW_MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a raw pixel-canvas image",
+            ));
+        }
+        let width = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let height = u32::from_le_bytes([header[8], header[9], header[10], header[11]]) as usize;
+        let len = match width.checked_mul(height).and_then(|n| n.checked_mul(3)) {
+            Some(len) => len,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "raw image dimensions are too large",
+                ))
+            }
+        };
+        // Read before allocating the image, so a corrupt header can't make
+        // us allocate more memory than there is data.
+        let mut bytes = Vec::new();
+        r.take(len as u64).read_to_end(&mut bytes)?;
+        if bytes.len() != len {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "raw image data is truncated",
+            ));
+        }
+        let pixels = bytes
+            .chunks(3)
+            .map(|pix| Color::rgb(pix[0], pix[1], pix[2]))
+            .collect();
+        Ok(Image::from_vec(width, height, pixels).expect("the data matches the dimensions"))
+    }
+
+    /// Apply a gamma curve to every channel of the image.
+    ///
+    /// Each channel is mapped as `c = 255 * (c / 255) ^ g`, so a `g` of
+    /// [`GAMMA`] (2.2) converts to linear light, and `1.0 / GAMMA` converts
+    /// back.
+    ///
+    /// [`GAMMA`]: ../color/constant.GAMMA.html
+    pub fn gamma(&mut self, g: f32) {
+        self.apply_table(|value| ((value as f32 / 255.0).powf(g) * 255.0).round() as u8);
+    }
+
+    /// Shift every channel of the image by `delta`, saturating at black and
+    /// white.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::filled(2, 2, Color::rgb(10, 100, 250));
+    /// image.brightness(20);
+    /// assert_eq!(image[XY(0, 0)], Color::rgb(30, 120, 255));
+    /// image.brightness(-40);
+    /// assert_eq!(image[XY(0, 0)], Color::rgb(0, 80, 215));
+    /// image.brightness(i16::MAX);
+    /// assert_eq!(image[XY(0, 0)], Color::WHITE);
+    /// ```
+    pub fn brightness(&mut self, delta: i16) {
+        self.apply_table(|value| (value as i32 + delta as i32).restrict(0..=255) as u8);
+    }
+
+    /// Scale every channel of the image away from (or towards) the middle
+    /// gray of 128, saturating at black and white.
+    ///
+    /// A `factor` above 1 increases the contrast, and a factor below 1
+    /// decreases it.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::filled(2, 2, Color::rgb(28, 128, 208));
+    /// image.contrast(2.0);
+    /// assert_eq!(image[XY(0, 0)], Color::rgb(0, 128, 255));
+    /// ```
+    pub fn contrast(&mut self, factor: f32) {
+        self.apply_table(|value| {
+            ((value as f32 - 128.0) * factor + 128.0)
+                .round()
+                .restrict(0.0..=255.0) as u8
+        });
+    }
+
+    /// Set each channel to 255 if it's at least `level`, or 0 otherwise.
+    ///
+    /// Each channel is thresholded separately, so colorful images end up
+    /// with up to eight colors. See
+    /// [`threshold_luminance`](#method.threshold_luminance) to get only black
+    /// and white.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::filled(2, 2, Color::rgb(200, 100, 128));
+    /// image.threshold(128);
+    /// assert_eq!(image[XY(0, 0)], Color::rgb(255, 0, 255));
+    /// ```
+    pub fn threshold(&mut self, level: u8) {
+        self.apply_table(|value| if value >= level { 255 } else { 0 });
+    }
+
+    /// Set each pixel to white if its [`luminance`] is at least `level`, or
+    /// black otherwise.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::filled(2, 2, Color::rgb(200, 100, 128));
+    /// image.threshold_luminance(128);
+    /// assert_eq!(image[XY(0, 0)], Color::BLACK);
+    /// ```
+    ///
+    /// [`luminance`]: ../color/struct.Color.html#method.luminance
+    pub fn threshold_luminance(&mut self, level: u8) {
+        for pix in &mut self.pixels {
+            *pix = if pix.luminance() >= level {
+                Color::WHITE
+            } else {
+                Color::BLACK
+            };
+        }
+    }
+
+    /// Reduce each channel to `levels` evenly spaced values, including 0 and
+    /// 255.
+    ///
+    /// Each channel is rounded to the nearest level. Fewer than 2 levels are
+    /// treated as 2.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::filled(2, 2, Color::rgb(20, 100, 200));
+    /// image.posterize(3);
+    /// assert_eq!(image[XY(0, 0)], Color::rgb(0, 128, 255));
+    /// ```
+    pub fn posterize(&mut self, levels: u8) {
+        let steps = levels.max(2) as f32 - 1.0;
+        self.apply_table(|value| {
+            ((value as f32 * steps / 255.0).round() * 255.0 / steps).round() as u8
+        });
+    }
+
+    /// Stretch each channel so the values it uses cover the full range from
+    /// 0 to 255.
+    ///
+    /// This is an "auto levels" pass, which rescues renders that come out
+    /// too dark or washed out. See
+    /// [`normalize_with`](#method.normalize_with) for more options.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::filled(2, 1, Color::rgb(50, 100, 100));
+    /// image[XY(1, 0)] = Color::rgb(150, 200, 100);
+    /// image.normalize();
+    /// assert_eq!(image[XY(0, 0)], Color::rgb(0, 0, 100));
+    /// assert_eq!(image[XY(1, 0)], Color::rgb(255, 255, 100));
+    /// ```
+    pub fn normalize(&mut self) {
+        self.normalize_with(Normalize::PerChannel, 0.0);
+    }
+
+    /// Stretch the range of the image to cover the full range from 0 to 255,
+    /// choosing how the channels are stretched.
+    ///
+    /// `clip` is the fraction of the pixels at each end of the range to
+    /// ignore, so a few outliers don't prevent the rest from being
+    /// stretched. For example, a `clip` of `0.01` saturates the darkest and
+    /// brightest 1% of the pixels. Channels with only one value are left
+    /// as-is.
+    /// ```rust
+    /// # use pixel_canvas::{prelude::*, image::Normalize};
+    /// let pixels = vec![Color::gray(64), Color::gray(192), Color::rgb(128, 96, 128)];
+    /// let mut image = Image::from_vec(3, 1, pixels).unwrap();
+    /// image.normalize_with(Normalize::Luminance, 0.0);
+    /// assert_eq!(image[XY(0, 0)], Color::BLACK);
+    /// assert_eq!(image[XY(1, 0)], Color::WHITE);
+    /// assert_eq!(image[XY(2, 0)], Color::rgb(128, 64, 128));
+    /// ```
+    pub fn normalize_with(&mut self, mode: Normalize, clip: f32) {
+        let tables = match mode {
+            Normalize::PerChannel => {
+                let [r, g, b] = self.histogram();
+                [
+                    stretch_table(&r, clip),
+                    stretch_table(&g, clip),
+                    stretch_table(&b, clip),
+                ]
+            }
+            Normalize::Luminance => [stretch_table(&self.luminance_histogram(), clip); 3],
+        };
+        for pix in &mut self.pixels {
+            pix.r = tables[0][pix.r as usize];
+            pix.g = tables[1][pix.g as usize];
+            pix.b = tables[2][pix.b as usize];
+        }
+    }
+
+    /// The largest difference between any channel of the pixels in two
+    /// images, or `None` if their dimensions don't match.
+    ///
+    /// This is useful for comparing images approximately, when rounding
+    /// might change pixels slightly.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let a = Image::filled(4, 4, Color::rgb(100, 100, 100));
+    /// let mut b = a.clone();
+    /// b[XY(3, 3)] = Color::rgb(98, 103, 100);
+    /// assert_eq!(a.max_channel_diff(&b), Some(3));
+    /// ```
+    pub fn max_channel_diff(&self, other: &Image) -> Option<u8> {
+        if (self.width, self.height) != (other.width, other.height) {
+            return None;
+        }
+        let channel_diff = |a: u8, b: u8| a.max(b) - a.min(b);
+        let pixel_diff = |(a, b): (&Color, &Color)| {
+            channel_diff(a.r, b.r)
+                .max(channel_diff(a.g, b.g))
+                .max(channel_diff(a.b, b.b))
+        };
+        let pixels = self.pixels.iter().zip(&other.pixels);
+        Some(pixels.map(pixel_diff).max().unwrap_or(0))
+    }
+
+    /// Count how many times each value appears in each channel.
+    ///
+    /// The histograms are in red, green, blue order, indexed by the channel
+    /// value.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::filled(2, 2, Color::BLACK);
+    /// image[XY(0, 0)] = Color::rgb(255, 0, 10);
+    /// let [r, g, b] = image.histogram();
+    /// assert_eq!((r[0], r[255]), (3, 1));
+    /// assert_eq!(g[0], 4);
+    /// assert_eq!((b[0], b[10]), (3, 1));
+    /// ```
+    pub fn histogram(&self) -> [[u32; 256]; 3] {
+        let mut histogram = [[0; 256]; 3];
+        for pix in &self.pixels {
+            histogram[0][pix.r as usize] += 1;
+            histogram[1][pix.g as usize] += 1;
+            histogram[2][pix.b as usize] += 1;
+        }
+        histogram
+    }
+
+    /// Count how many times each [`luminance`] appears in the image.
+    ///
+    /// [`luminance`]: ../color/struct.Color.html#method.luminance
+    pub fn luminance_histogram(&self) -> [u32; 256] {
+        let mut histogram = [0; 256];
+        for pix in &self.pixels {
+            histogram[pix.luminance() as usize] += 1;
+        }
+        histogram
+    }
+
+    /// Apply a function to every channel of the image, through a lookup
+    /// table.
+    fn apply_table(&mut self, f: impl Fn(u8) -> u8) {
+        let mut table = [0u8; 256];
+        for (i, entry) in table.iter_mut().enumerate() {
+            *entry = f(i as u8);
+        }
+        for pix in &mut self.pixels {
+            pix.r = table[pix.r as usize];
+            pix.g = table[pix.g as usize];
+            pix.b = table[pix.b as usize];
+        }
+    }
+
+    /// Create a grayscale copy of the image, using the Rec. 709 luminance.
+    pub fn to_grayscale(&self) -> Image {
+        self.to_grayscale_with(Luma::Rec709)
+    }
+
+    /// Create a grayscale copy of the image, using the luminance weights of
+    /// the given standard.
+    pub fn to_grayscale_with(&self, weights: Luma) -> Image {
+        Image {
+            width: self.width,
+            height: self.height,
+            pixels: self
+                .pixels
+                .iter()
+                .map(|pix| Color::gray(pix.luminance_with(weights)))
+                .collect(),
+            dirty: Vec::new(),
+        }
+    }
+
+    /// Fill the image with a gradient from the top of the window to the
+    /// bottom.
+    ///
+    /// Rows are stored from the bottom up, so row 0 is `bottom`, and the
+    /// last row is `top`.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::new(2, 4);
+    /// image.fill_vertical_gradient(Color::WHITE, Color::BLACK);
+    /// assert_eq!(image[XY(0, 0)], Color::BLACK);
+    /// assert_eq!(image[XY(0, 3)], Color::WHITE);
+    /// ```
+    pub fn fill_vertical_gradient(&mut self, top: Color, bottom: Color) {
+        let last = self.height.saturating_sub(1).max(1) as f32;
+        let colors: Vec<Color> = (0..self.height)
+            .map(|y| bottom.blend(top, y as f32 / last))
+            .collect();
+        for (i, pix) in self.pixels.iter_mut().enumerate() {
+            *pix = colors[i / self.width];
+        }
+    }
+
+    /// Fill the image with a gradient from the left column to the right
+    /// column.
+    pub fn fill_horizontal_gradient(&mut self, left: Color, right: Color) {
+        let last = self.width.saturating_sub(1).max(1) as f32;
+        let colors: Vec<Color> = (0..self.width)
+            .map(|x| left.blend(right, x as f32 / last))
+            .collect();
+        for (i, pix) in self.pixels.iter_mut().enumerate() {
+            *pix = colors[i % self.width];
+        }
+    }
+
+    /// Fill the image with a linear gradient between two points.
+    ///
+    /// Each pixel is projected onto the line from `start` to `end`, pixels
+    /// before `start` get the `from` color, and pixels past `end` get the
+    /// `to` color.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::new(3, 3);
+    /// image.fill_gradient(XY(0, 0), XY(2, 2), Color::BLACK, Color::WHITE);
+    /// assert_eq!(image[XY(0, 0)], Color::BLACK);
+    /// assert_eq!(image[XY(2, 0)], Color::gray(127));
+    /// assert_eq!(image[XY(2, 2)], Color::WHITE);
+    /// ```
+    pub fn fill_gradient(&mut self, start: XY, end: XY, from: Color, to: Color) {
+        let (x0, y0) = (start.0 as f32, start.1 as f32);
+        let (dx, dy) = (end.0 as f32 - x0, end.1 as f32 - y0);
+        let len2 = (dx * dx + dy * dy).max(f32::EPSILON);
+        for (i, pix) in self.pixels.iter_mut().enumerate() {
+            let (x, y) = ((i % self.width) as f32, (i / self.width) as f32);
+            let t = ((x - x0) * dx + (y - y0) * dy) / len2;
+            *pix = from.blend(to, t.restrict(0.0..=1.0));
+        }
+    }
+
+    /// Fill the contiguous region around a seed pixel, like a paint bucket.
+    ///
+    /// Pixels are part of the region if they're connected horizontally or
+    /// vertically to the seed, and each of their channels is within
+    /// `tolerance` of the seed's color. Returns the number of pixels filled,
+    /// which is zero if the seed is out of bounds.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::new(4, 4);
+    /// image[XY(1, 0)] = Color::WHITE;
+    /// image[XY(0, 1)] = Color::WHITE;
+    /// assert_eq!(image.flood_fill(XY(3, 3), Color::gray(50), 0), 13);
+    /// assert_eq!(image[XY(0, 0)], Color::BLACK);
+    /// ```
+    pub fn flood_fill(&mut self, seed: XY, fill: Color, tolerance: u8) -> usize {
+        let XY(x, y) = seed;
+        if x >= self.width || y >= self.height {
+            return 0;
+        }
+        let target = self.pixels[y * self.width + x];
+        let matches = |pix: Color| {
+            let diff = |a: u8, b: u8| a.max(b) - a.min(b);
+            diff(pix.r, target.r) <= tolerance
+                && diff(pix.g, target.g) <= tolerance
+                && diff(pix.b, target.b) <= tolerance
+        };
+        let mut visited = vec![false; self.pixels.len()];
+        let mut queue = std::collections::VecDeque::new();
+        queue.push_back((x, y));
+        visited[y * self.width + x] = true;
+        let mut filled = 0;
+        while let Some((x, y)) = queue.pop_front() {
+            self.pixels[y * self.width + x] = fill;
+            filled += 1;
+            let neighbors = [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ];
+            for &(nx, ny) in &neighbors {
+                if nx >= self.width || ny >= self.height {
+                    continue;
+                }
+                let i = ny * self.width + nx;
+                if !visited[i] && matches(self.pixels[i]) {
+                    visited[i] = true;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+        filled
+    }
+
+    /// Reduce the image to the colors of a palette, with Floyd–Steinberg
+    /// dithering.
+    ///
+    /// Each pixel is replaced with the nearest palette color, and the
+    /// difference is spread onto the neighboring pixels that haven't been
+    /// visited yet, which trades banding for a fine noise pattern. Does
+    /// nothing if the palette is empty.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::new(64, 64);
+    /// image.fill_horizontal_gradient(Color::BLACK, Color::WHITE);
+    /// image.dither(&[Color::BLACK, Color::WHITE]);
+    /// assert!(image.iter().all(|&pix| pix == Color::BLACK || pix == Color::WHITE));
+    /// ```
+    pub fn dither(&mut self, palette: &[Color]) {
+        if palette.is_empty() {
+            return;
+        }
+        let width = self.width;
+        // The error carried into the current and next rows, per channel.
+        let mut current = vec![[0.0f32; 3]; width + 2];
+        let mut next = vec![[0.0f32; 3]; width + 2];
+        for y in 0..self.height {
+            for x in 0..width {
+                let pix = &mut self.pixels[y * width + x];
+                let err = current[x + 1];
+                let wanted = [
+                    pix.r as f32 + err[0],
+                    pix.g as f32 + err[1],
+                    pix.b as f32 + err[2],
+                ];
+                let clamped = Color {
+                    r: wanted[0].restrict(0.0..=255.0) as u8,
+                    g: wanted[1].restrict(0.0..=255.0) as u8,
+                    b: wanted[2].restrict(0.0..=255.0) as u8,
+                };
+                *pix = clamped.nearest(palette);
+                let got = [pix.r as f32, pix.g as f32, pix.b as f32];
+                for c in 0..3 {
+                    let err = wanted[c] - got[c];
+                    current[x + 2][c] += err * 7.0 / 16.0;
+                    next[x][c] += err * 3.0 / 16.0;
+                    next[x + 1][c] += err * 5.0 / 16.0;
+                    next[x + 2][c] += err * 1.0 / 16.0;
+                }
+            }
+            std::mem::swap(&mut current, &mut next);
+            next.fill([0.0; 3]);
+        }
+    }
+
+    /// Replace every pixel with the nearest color in a palette, without
+    /// dithering.
+    ///
+    /// See [`Color::nearest`] for how distance is measured. Does nothing if
+    /// the palette is empty.
+    ///
+    /// [`Color::nearest`]: ../color/struct.Color.html#method.nearest
+    pub fn quantize(&mut self, palette: &[Color]) {
         for pix in &mut self.pixels {
-            *pix = color;
+            *pix = pix.nearest(palette);
         }
     }
+
+    /// Sample the image at a fractional position, interpolating between the
+    /// four nearest pixels.
+    ///
+    /// Pixel centers are at integer coordinates, so sampling at `(1.0, 2.0)`
+    /// gives exactly `image[XY(1, 2)]`. Positions outside of the image are
+    /// clamped to the nearest edge. An empty image samples as black.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let image = Image::from_vec(2, 1, vec![Color::BLACK, Color::WHITE]).unwrap();
+    /// assert_eq!(image.sample_bilinear(0.5, 0.0), Color::gray(128));
+    /// assert_eq!(image.sample_bilinear(5.0, -3.0), Color::WHITE);
+    /// assert_eq!(image.sample_bilinear(1e30, -1e30), Color::WHITE);
+    /// ```
+    pub fn sample_bilinear(&self, x: f32, y: f32) -> Color {
+        let (w, h) = (self.width as isize, self.height as isize);
+        self.sample_with(x, y, |x, y| {
+            (x.restrict(0..w) as usize, y.restrict(0..h) as usize)
+        })
+    }
+
+    /// Sample the image at a fractional position like
+    /// [`sample_bilinear`](#method.sample_bilinear), but tiling the image
+    /// instead of clamping at the edges.
+    ///
+    /// This is useful for seamless textures, since sampling between the last
+    /// and first columns interpolates between them.
+    pub fn sample_wrap(&self, x: f32, y: f32) -> Color {
+        let (w, h) = (self.width as isize, self.height as isize);
+        self.sample_with(x, y, |x, y| {
+            (x.rem_euclid(w) as usize, y.rem_euclid(h) as usize)
+        })
+    }
+
+    fn sample_with(&self, x: f32, y: f32, index: impl Fn(isize, isize) -> (usize, usize)) -> Color {
+        if self.pixels.is_empty() {
+            return Color::BLACK;
+        }
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as isize, y0 as isize);
+        let mut sum = [0.0f32; 3];
+        for &(dx, dy, weight) in &[
+            (0, 0, (1.0 - fx) * (1.0 - fy)),
+            (1, 0, fx * (1.0 - fy)),
+            (0, 1, (1.0 - fx) * fy),
+            (1, 1, fx * fy),
+        ] {
+            // Huge coordinates saturate when cast, so the neighbors can't overflow.
+            let (x, y) = index(x0.saturating_add(dx), y0.saturating_add(dy));
+            let pix = self.pixels[y * self.width + x];
+            sum[0] += pix.r as f32 * weight;
+            sum[1] += pix.g as f32 * weight;
+            sum[2] += pix.b as f32 * weight;
+        }
+        Color {
+            r: sum[0].round() as u8,
+            g: sum[1].round() as u8,
+            b: sum[2].round() as u8,
+        }
+    }
+
+    /// Plot a point at a fractional position, spreading it over the four
+    /// nearest pixels by how much of each one it covers.
+    ///
+    /// Like [`sample_bilinear`](#method.sample_bilinear), pixel centers are at
+    /// integer coordinates, so plotting at `(1.0, 2.0)` only touches
+    /// `image[XY(1, 2)]`. Each pixel is blended towards the color by its
+    /// coverage, and any part of the point outside of the image is dropped.
+    /// Points at NaN or infinite positions aren't drawn.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::new(2, 1);
+    /// image.plot_aa(0.5, 0.0, Color::WHITE);
+    /// assert_eq!(image[XY(0, 0)], Color::gray(127));
+    /// assert_eq!(image[XY(1, 0)], Color::gray(127));
+    ///
+    /// image.plot_aa(f32::NAN, 0.0, Color::WHITE);
+    /// image.plot_aa(1e30, 0.0, Color::WHITE);
+    /// assert_eq!(image[XY(0, 0)], Color::gray(127));
+    /// ```
+    pub fn plot_aa(&mut self, x: f32, y: f32, color: Color) {
+        if !x.is_finite() || !y.is_finite() {
+            return;
+        }
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as isize, y0 as isize);
+        for &(dx, dy, weight) in &[
+            (0, 0, (1.0 - fx) * (1.0 - fy)),
+            (1, 0, fx * (1.0 - fy)),
+            (0, 1, (1.0 - fx) * fy),
+            (1, 1, fx * fy),
+        ] {
+            let (x, y) = (x0.saturating_add(dx), y0.saturating_add(dy));
+            if weight <= 0.0
+                || x < 0
+                || y < 0
+                || x >= self.width as isize
+                || y >= self.height as isize
+            {
+                continue;
+            }
+            let pix = &mut self.pixels[y as usize * self.width + x as usize];
+            *pix = pix.blend(color, weight);
+        }
+    }
+
+    /// Draw an antialiased line with a given width and round caps.
+    ///
+    /// Like [`plot_aa`](#method.plot_aa), pixel centers are at whole
+    /// numbers, so the ends can be placed between pixels. Pixels along the
+    /// edges of the line are blended with the color by how much of them the
+    /// line covers. Parts of the line outside of the image are clipped.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::new(16, 16);
+    /// image.draw_line_thick(Vec2::xy(2.0, 8.0), Vec2::xy(13.0, 8.0), 3.0, Color::WHITE);
+    /// assert_eq!(image[XY(8, 8)], Color::WHITE);
+    /// assert_eq!(image[XY(8, 9)], Color::WHITE);
+    /// assert_eq!(image[XY(8, 11)], Color::BLACK);
+    /// ```
+    pub fn draw_line_thick(&mut self, from: Vec2, to: Vec2, width: f32, color: Color) {
+        self.draw_polyline(&[from, to], width, color);
+    }
+
+    /// Draw an antialiased quadratic Bezier curve, from `p0` to `p2`, bending
+    /// towards the control point `p1`.
+    ///
+    /// The curve is split into straight pieces, more of them where it bends
+    /// more tightly, and each piece is drawn one pixel wide like
+    /// [`draw_line_thick`](#method.draw_line_thick).
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::new(32, 32);
+    /// let (p0, p1, p2) = (Vec2::xy(2.0, 2.0), Vec2::xy(16.0, 30.0), Vec2::xy(30.0, 2.0));
+    /// image.draw_quad_bezier(p0, p1, p2, Color::WHITE);
+    /// // The middle of the curve is halfway to the control point.
+    /// assert_eq!(image[XY(16, 16)], Color::WHITE);
+    ///
+    /// // Where the straight pieces meet, pixels are only blended once, so a
+    /// // curve between two rows covers both evenly.
+    /// let mut image = Image::new(32, 32);
+    /// let (p0, p1, p2) = (Vec2::xy(2.0, 8.5), Vec2::xy(4.0, 8.5), Vec2::xy(30.0, 8.5));
+    /// image.draw_quad_bezier(p0, p1, p2, Color::WHITE);
+    /// assert!((4..28).all(|x| image[XY(x, 8)] == Color::gray(127)));
+    /// ```
+    pub fn draw_quad_bezier(&mut self, p0: Vec2, p1: Vec2, p2: Vec2, color: Color) {
+        let mut points = vec![p0];
+        flatten_quad(p0, p1, p2, MAX_BEZIER_DEPTH, &mut points);
+        self.draw_polyline(&points, 1.0, color);
+    }
+
+    /// Draw an antialiased cubic Bezier curve, from `p0` to `p3`, with the
+    /// control points `p1` and `p2`.
+    ///
+    /// The curve leaves `p0` towards `p1`, and arrives at `p3` from the
+    /// direction of `p2`. It's drawn like
+    /// [`draw_quad_bezier`](#method.draw_quad_bezier).
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::new(32, 32);
+    /// image.draw_cubic_bezier(
+    ///     Vec2::xy(2.0, 16.0),
+    ///     Vec2::xy(10.0, 30.0),
+    ///     Vec2::xy(22.0, 2.0),
+    ///     Vec2::xy(30.0, 16.0),
+    ///     Color::WHITE,
+    /// );
+    /// // The curve is symmetric around the center.
+    /// assert_eq!(image[XY(16, 16)], Color::WHITE);
+    /// ```
+    pub fn draw_cubic_bezier(&mut self, p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2, color: Color) {
+        let mut points = vec![p0];
+        flatten_cubic(p0, p1, p2, p3, MAX_BEZIER_DEPTH, &mut points);
+        self.draw_polyline(&points, 1.0, color);
+    }
+
+    /// Draw antialiased lines with round caps connecting a list of points.
+    ///
+    /// The coverage of each pixel is the largest coverage of any of the
+    /// lines, so pixels where the lines meet are only blended once.
+    fn draw_polyline(&mut self, points: &[Vec2], width: f32, color: Color) {
+        if width <= 0.0 || self.pixels.is_empty() || points.is_empty() {
+            return;
+        }
+        // A single point is drawn as a dot.
+        let dot;
+        let points = if points.len() == 1 {
+            dot = [points[0], points[0]];
+            &dot[..]
+        } else {
+            points
+        };
+        // Pixels within half a pixel of the edge are partially covered.
+        let reach = width / 2.0 + 0.5;
+        let (width, height) = (self.width, self.height);
+        let clip = |lo: f32, hi: f32, len: usize| {
+            let lo = (lo - reach).floor().max(0.0) as usize;
+            let hi = ((hi + reach).ceil().max(0.0) as usize).min(len - 1);
+            lo..=hi
+        };
+        let bounds = |pick: fn(Vec2) -> f32| {
+            let values = points.iter().map(|&p| pick(p));
+            let lo = values.clone().fold(f32::INFINITY, f32::min);
+            (lo, values.fold(f32::NEG_INFINITY, f32::max))
+        };
+        let ((x_lo, x_hi), (y_lo, y_hi)) = (bounds(|p| p.x), bounds(|p| p.y));
+        let (columns, rows) = (clip(x_lo, x_hi, width), clip(y_lo, y_hi, height));
+        if columns.is_empty() || rows.is_empty() {
+            return;
+        }
+        let (x0, y0) = (*columns.start(), *rows.start());
+        let stride = columns.end() - x0 + 1;
+        let mut coverage = vec![0.0f32; stride * (rows.end() - y0 + 1)];
+        for pair in points.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            let line = to - from;
+            let len2 = line.len2();
+            let columns = clip(from.x.min(to.x), from.x.max(to.x), width);
+            for y in clip(from.y.min(to.y), from.y.max(to.y), height) {
+                for x in columns.clone() {
+                    let p = Vec2::xy(x as f32, y as f32) - from;
+                    let t = if len2 > 0.0 {
+                        (p.dot(line) / len2).restrict(0.0..=1.0)
+                    } else {
+                        0.0
+                    };
+                    let cover = &mut coverage[(y - y0) * stride + (x - x0)];
+                    *cover = cover.max(reach - (p - line * t).len());
+                }
+            }
+        }
+        for (y, row) in coverage.chunks(stride).enumerate() {
+            for (x, &cover) in row.iter().enumerate() {
+                let cover = cover.restrict(0.0..=1.0);
+                if cover > 0.0 {
+                    let pix = &mut self.pixels[(y0 + y) * width + x0 + x];
+                    *pix = pix.blend(color, cover);
+                }
+            }
+        }
+    }
+
+    /// Draw text into the image with a small built-in bitmap font.
+    ///
+    /// The origin is the lower-left corner of the first character. Each
+    /// character is 5x7 pixels with a pixel of spacing, and a `'\n'` starts
+    /// a new line below the current one. Only printable ASCII is supported,
+    /// and any other characters are drawn as `?`. Text running off the edge
+    /// of the image is clipped.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let mut image = Image::new(32, 16);
+    /// image.draw_text(XY(1, 8), "Hi!\nok", Color::WHITE);
+    /// // The top of the `H`'s left stem.
+    /// assert_eq!(image[XY(1, 14)], Color::WHITE);
+    /// assert_eq!(image[XY(2, 14)], Color::BLACK);
+    /// ```
+    pub fn draw_text(&mut self, origin: XY, text: &str, color: Color) {
+        let XY(x0, y0) = origin;
+        let (mut x, mut y) = (x0 as isize, y0 as isize);
+        for c in text.chars() {
+            if c == '\n' {
+                x = x0 as isize;
+                y -= font::LINE_HEIGHT as isize;
+                continue;
+            }
+            for (row, &bits) in font::glyph(c).iter().enumerate() {
+                let py = y + (font::GLYPH_HEIGHT - 1 - row) as isize;
+                if py < 0 || py >= self.height as isize {
+                    continue;
+                }
+                for col in 0..font::GLYPH_WIDTH {
+                    let px = x + col as isize;
+                    if bits & (1 << (font::GLYPH_WIDTH - 1 - col)) == 0
+                        || px < 0
+                        || px >= self.width as isize
+                    {
+                        continue;
+                    }
+                    self.pixels[py as usize * self.width + px as usize] = color;
+                }
+            }
+            x += font::ADVANCE as isize;
+        }
+    }
+}
+
+impl FloatImage {
+    /// Convert the image to 8-bit color for display, compressing its range
+    /// with a tone mapping operator, and then gamma encoding it.
+    /// ```rust
+    /// # use pixel_canvas::{prelude::*, color::{LinearColor, ToneMap}, image::FloatImage};
+    /// let mut light = FloatImage::filled(4, 4, LinearColor::default());
+    /// for _ in 0..10 {
+    ///     for pix in light.iter_mut() {
+    ///         *pix = *pix + LinearColor::rgb(0.3, 0.1, 0.0);
+    ///     }
+    /// }
+    /// let image = light.tone_map(ToneMap::Reinhard);
+    /// assert_eq!(image[XY(0, 0)], Color::rgb(224, 186, 0));
+    /// ```
+    pub fn tone_map(&self, operator: ToneMap) -> Image {
+        Image {
+            width: self.width,
+            height: self.height,
+            pixels: self
+                .pixels
+                .iter()
+                .map(|pix| Color::from_linear(pix.tone_map(operator)))
+                .collect(),
+            dirty: Vec::new(),
+        }
+    }
+}
+
+/// A mutable view of a rectangle of an [`Image`], from
+/// [`Image::sub_image_mut`] or [`Image::tiles_mut`].
+///
+/// It's indexed by x/y positions relative to its own lower-left corner.
+///
+/// [`Image`]: struct.Image.html
+/// [`Image::sub_image_mut`]: struct.Image.html#method.sub_image_mut
+/// [`Image::tiles_mut`]: struct.Image.html#method.tiles_mut
+pub struct ImageViewMut<'a, P = Color> {
+    origin: XY,
+    width: usize,
+    rows: Vec<&'a mut [P]>,
 }
 
-impl Index<RC> for Image {
-    type Output = Color;
+impl<'a, P: Pixel> ImageViewMut<'a, P> {
+    /// The position of the view's lower-left corner in the image.
+    pub fn origin(&self) -> XY {
+        self.origin
+    }
+
+    /// The width of the view in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The height of the view in pixels.
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Get the pixel at an x/y position in the view, or `None` if it's out
+    /// of bounds.
+    pub fn get(&self, XY(x, y): XY) -> Option<&P> {
+        self.rows.get(y)?.get(x)
+    }
+
+    /// Mutably get the pixel at an x/y position in the view, or `None` if
+    /// it's out of bounds.
+    pub fn get_mut(&mut self, XY(x, y): XY) -> Option<&mut P> {
+        self.rows.get_mut(y)?.get_mut(x)
+    }
+
+    /// Mutably borrow a row of the view, counting from the bottom row.
+    ///
+    /// Panics if the row is out of bounds.
+    pub fn row_mut(&mut self, y: usize) -> &mut [P] {
+        self.rows[y]
+    }
+
+    /// Fill the view with a single solid color.
+    pub fn fill(&mut self, color: P) {
+        for row in &mut self.rows {
+            fill_slice(row, color);
+        }
+    }
+}
+
+impl<'a, P> Index<XY> for ImageViewMut<'a, P> {
+    type Output = P;
+    fn index(&self, XY(x, y): XY) -> &Self::Output {
+        &self.rows[y][x]
+    }
+}
+
+impl<'a, P> IndexMut<XY> for ImageViewMut<'a, P> {
+    fn index_mut(&mut self, XY(x, y): XY) -> &mut Self::Output {
+        &mut self.rows[y][x]
+    }
+}
+
+/// A lookup table stretching the values in a histogram to cover the full
+/// range, ignoring `clip` of the values at each end.
+fn stretch_table(histogram: &[u32; 256], clip: f32) -> [u8; 256] {
+    let total: u32 = histogram.iter().sum();
+    let clipped = (total as f32 * clip.restrict(0.0..=0.5)) as u32;
+    let mut count = 0;
+    let lo = histogram.iter().position(|&n| {
+        count += n;
+        count > clipped
+    });
+    let mut count = 0;
+    let hi = histogram.iter().rposition(|&n| {
+        count += n;
+        count > clipped
+    });
+    let mut table = [0; 256];
+    for (i, entry) in table.iter_mut().enumerate() {
+        *entry = match (lo, hi) {
+            (Some(lo), Some(hi)) if lo < hi => ((i as f32 - lo as f32) * 255.0 / (hi - lo) as f32)
+                .round()
+                .restrict(0.0..=255.0) as u8,
+            _ => i as u8,
+        };
+    }
+    table
+}
+
+/// How far a curve can stray from a straight line, in pixels, before it's
+/// subdivided further.
+const BEZIER_FLATNESS: f32 = 0.25;
+
+/// The maximum number of times a curve is split in half, which also keeps
+/// curves with non-finite points from subdividing forever.
+const MAX_BEZIER_DEPTH: u32 = 16;
+
+/// The point halfway between two points.
+fn midpoint(a: Vec2, b: Vec2) -> Vec2 {
+    (a + b) * 0.5
+}
+
+/// Split a quadratic Bezier curve into straight pieces, pushing the end of
+/// each piece onto `points`.
+fn flatten_quad(p0: Vec2, p1: Vec2, p2: Vec2, depth: u32, points: &mut Vec<Vec2>) {
+    // How far the control point pulls the curve away from the chord.
+    let bend = (p0 - p1 * 2.0 + p2).len();
+    if depth == 0 || bend <= BEZIER_FLATNESS {
+        points.push(p2);
+        return;
+    }
+    let (a, b) = (midpoint(p0, p1), midpoint(p1, p2));
+    let mid = midpoint(a, b);
+    flatten_quad(p0, a, mid, depth - 1, points);
+    flatten_quad(mid, b, p2, depth - 1, points);
+}
+
+/// Split a cubic Bezier curve into straight pieces, pushing the end of each
+/// piece onto `points`.
+fn flatten_cubic(p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2, depth: u32, points: &mut Vec<Vec2>) {
+    let bend = (p0 - p1 * 2.0 + p2).len().max((p1 - p2 * 2.0 + p3).len());
+    if depth == 0 || bend <= BEZIER_FLATNESS {
+        points.push(p3);
+        return;
+    }
+    let (a, b, c) = (midpoint(p0, p1), midpoint(p1, p2), midpoint(p2, p3));
+    let (ab, bc) = (midpoint(a, b), midpoint(b, c));
+    let mid = midpoint(ab, bc);
+    flatten_cubic(p0, a, ab, mid, depth - 1, points);
+    flatten_cubic(mid, bc, c, p3, depth - 1, points);
+}
+
+/// An image of palette indices, for indexed color art.
+///
+/// The pixels are `u8` indices into the [`Palette`], and are only turned into
+/// colors when the image is expanded for display. Changing the palette
+/// recolors the whole image without touching the pixels, which is how
+/// classic color cycling animations work.
+/// ```rust
+/// # use pixel_canvas::{prelude::*, color::Palette, image::IndexedImage};
+/// let palette = Palette::new(vec![Color::BLACK, Color::RED, Color::BLUE]);
+/// let mut indexed = IndexedImage::new(4, 4, palette);
+/// indexed.indices[XY(1, 2)] = 1;
+/// assert_eq!(indexed.expand()[XY(1, 2)], Color::RED);
+/// indexed.palette[1] = Color::WHITE;
+/// assert_eq!(indexed.expand()[XY(1, 2)], Color::WHITE);
+/// ```
+///
+/// [`Palette`]: ../color/struct.Palette.html
+#[derive(Clone, PartialEq)]
+pub struct IndexedImage {
+    /// The palette index of each pixel.
+    pub indices: GrayImage,
+    /// The colors the indices refer to.
+    pub palette: Palette,
+}
+
+impl IndexedImage {
+    /// Create an indexed image with every pixel set to index `0`.
+    pub fn new(width: usize, height: usize, palette: Palette) -> IndexedImage {
+        IndexedImage {
+            indices: Image::filled(width, height, 0),
+            palette,
+        }
+    }
+
+    /// The width of the image.
+    pub fn width(&self) -> usize {
+        self.indices.width()
+    }
+
+    /// The height of the image.
+    pub fn height(&self) -> usize {
+        self.indices.height()
+    }
+
+    /// Look up the color of every pixel in the palette.
+    pub fn expand(&self) -> Image {
+        let mut image = Image::new(self.width(), self.height());
+        self.expand_into(&mut image);
+        image
+    }
+
+    /// Look up the color of every pixel in the palette, writing them into an
+    /// existing image, like the one given to a render callback.
+    ///
+    /// If the sizes don't match, the image is replaced with one the size of
+    /// the indexed image.
+    /// ```rust,no_run
+    /// # use pixel_canvas::{prelude::*, color::Palette, image::IndexedImage};
+    /// let palette = Palette::new(vec![Color::BLACK, Color::RED, Color::BLUE]);
+    /// Canvas::new(320, 200)
+    ///     .state(IndexedImage::new(320, 200, palette))
+    ///     .render(|indexed, image| {
+    ///         indexed.palette.rotate(1, 2, 1);
+    ///         indexed.expand_into(image);
+    ///     });
+    /// ```
+    pub fn expand_into(&self, image: &mut Image) {
+        if image.width() != self.width() || image.height() != self.height() {
+            *image = Image::new(self.width(), self.height());
+        }
+        let mut lookup = [Color::BLACK; 256];
+        for (entry, &color) in lookup.iter_mut().zip(self.palette.colors()) {
+            *entry = color;
+        }
+        for (pix, &index) in image.iter_mut().zip(self.indices.iter()) {
+            *pix = lookup[index as usize];
+        }
+    }
+}
+
+/// The error returned when pixel data doesn't match the image dimensions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DimensionError {
+    /// The requested width.
+    pub width: usize,
+    /// The requested height.
+    pub height: usize,
+    /// The number of pixels that were actually provided.
+    pub len: usize,
+}
+
+impl fmt::Display for DimensionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "a {}x{} image needs {} pixels, but got {}",
+            self.width,
+            self.height,
+            self.width * self.height,
+            self.len
+        )
+    }
+}
+
+impl Error for DimensionError {}
+
+impl<P> Index<RC> for Image<P> {
+    type Output = P;
     fn index(&self, RC(row, col): RC) -> &Self::Output {
         &self.pixels[(row * self.width + col) as usize]
     }
 }
 
-impl IndexMut<RC> for Image {
+impl<P> IndexMut<RC> for Image<P> {
     fn index_mut(&mut self, RC(row, col): RC) -> &mut Self::Output {
         &mut self.pixels[(row * self.width + col) as usize]
     }
 }
 
-impl Index<XY> for Image {
-    type Output = Color;
+impl<P> Index<XY> for Image<P> {
+    type Output = P;
     fn index(&self, XY(x, y): XY) -> &Self::Output {
         &self.pixels[(y * self.width + x) as usize]
     }
 }
 
-impl IndexMut<XY> for Image {
+impl<P> IndexMut<XY> for Image<P> {
     fn index_mut(&mut self, XY(x, y): XY) -> &mut Self::Output {
         &mut self.pixels[(y * self.width + x) as usize]
     }
 }
 
-impl Deref for Image {
-    type Target = [Color];
+// Rectangles marked dirty don't affect equality, only the dimensions and
+// pixels do.
+impl<P: PartialEq> PartialEq for Image<P> {
+    fn eq(&self, other: &Image<P>) -> bool {
+        self.width == other.width && self.height == other.height && self.pixels == other.pixels
+    }
+}
+
+impl<P> Deref for Image<P> {
+    type Target = [P];
     fn deref(&self) -> &Self::Target {
         &self.pixels
     }
 }
 
-impl DerefMut for Image {
+impl<P> DerefMut for Image<P> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.pixels
     }
 }
 
-impl<'a> Texture2dDataSource<'a> for &'a Image {
-    type Data = u8;
+impl<'a, P: Pixel> Texture2dDataSource<'a> for &'a Image<P> {
+    type Data = P::Texel;
     fn into_raw(self) -> RawImage2d<'a, Self::Data> {
         RawImage2d {
-            data: Cow::Borrowed(unsafe {
-                std::slice::from_raw_parts(self.pixels.as_ptr() as *const u8, self.pixels.len() * 3)
-            }),
+            data: P::upload(&self.pixels),
             width: self.width as u32,
             height: self.height as u32,
-            format: ClientFormat::U8U8U8,
+            format: P::Texel::get_format(),
         }
     }
 }
@@ -7,12 +7,24 @@
 
 // @Todo: Write docs on how write your own input handler.
 
-use crate::canvas::CanvasInfo;
+use crate::canvas::{letterbox, CanvasInfo};
+/// Re-export the gamepad buttons and axes used by [`GamepadState`].
+///
+/// [`GamepadState`]: struct.GamepadState.html
+#[cfg(feature = "gamepad")]
+pub use gilrs::{Axis, Button};
 /// Re-export the glutin module for writing your own event handlers.
 pub use glium::glutin;
+use glium::glutin::dpi::PhysicalPosition;
 /// Re-export some common event types that are useful when writing your own
 /// event handlers.
-pub use glium::glutin::event::{Event, WindowEvent};
+pub use glium::glutin::event::{
+    DeviceEvent, ElementState, Event, KeyboardInput, MouseButton, Touch, TouchPhase,
+    VirtualKeyCode, WindowEvent,
+};
+#[cfg(feature = "gamepad")]
+use std::collections::HashMap;
+use std::collections::HashSet;
 
 /// An input handler that tracks the position of the mouse.
 ///
@@ -22,20 +34,69 @@ pub use glium::glutin::event::{Event, WindowEvent};
 ///   account for DPI. You don't want this very often, but if you want to match
 ///   the OS coordinates for some reason, this is it.
 /// - Physical coordinates (`x` and `y`) match the pixels in the image. This is
-///   usually what you want.
+///   usually what you want. With
+///   [`supersample`](../canvas/struct.Canvas.html#method.supersample), they
+///   match the larger image given to the render callback.
+/// - Precise coordinates (`x_f32` and `y_f32`) are the physical coordinates
+///   without rounding to a pixel, for smooth antialiased drawing with
+///   [`Image::plot_aa`](../image/struct.Image.html#method.plot_aa).
+///
+/// It also tracks the relative motion of the mouse (`delta_x` and `delta_y`),
+/// which keeps working when the cursor is grabbed or at the edge of the
+/// screen. This is what you want for camera controls. The motion is reset
+/// after each rendered frame, so it's the motion since the last frame.
+///
+/// The mouse buttons that are currently held down are in `buttons`.
+///
+/// Row 0 of the image is displayed at the bottom of the window, so by
+/// default the physical coordinates start from the lower-left corner, and
+/// `image[XY(mouse.x as usize, mouse.y as usize)]` is the pixel under the
+/// cursor. If you're flipping your image to draw with the origin in the
+/// upper-left corner instead, use [`top_left`](#method.top_left) so the mouse
+/// agrees with your drawing.
+///
+/// With [`preserve_aspect`](../canvas/struct.Canvas.html#method.preserve_aspect),
+/// the image doesn't fill the window, and positions over the black bars fall
+/// outside of the image: `x` outside of `0..width` or `y` outside of
+/// `0..height`. Check them before indexing the image.
 pub struct MouseState {
-    /// The x position from the lower-left corner, measured in physical pixels.
-    /// This should always correspond to the column of the pixel in the image.
+    /// The x position from the left edge, measured in physical pixels.
+    /// This should always correspond to the column of the pixel in the image,
+    /// or be outside of the image when the cursor is over the black bars of
+    /// `preserve_aspect`.
     pub x: i32,
-    /// The y position from the lower-left corner, measured in physical pixels.
-    /// This should always correspond to the row of the pixel in the image.
+    /// The y position from the bottom edge (or the top edge, if `top_left`
+    /// is set), measured in physical pixels. By default, this corresponds to
+    /// the row of the pixel in the image.
     pub y: i32,
+    /// The x position in physical pixels, including the fraction of a pixel.
+    /// Pixel centers are at whole numbers, like
+    /// [`Image::plot_aa`](../image/struct.Image.html#method.plot_aa) expects,
+    /// so it's always within half a pixel of `x`.
+    pub x_f32: f32,
+    /// The y position in physical pixels, including the fraction of a pixel.
+    /// It's always within half a pixel of `y`.
+    pub y_f32: f32,
     /// The x position from the upper-left corner as reported by the OS,
     /// measured in virtual pixels.
     pub virtual_x: i32,
     /// The y position from the upper-left corner as reported by the OS,
     /// measured in virtual pixels.
     pub virtual_y: i32,
+    /// The horizontal mouse motion since the last frame was rendered, in
+    /// unspecified device units.
+    ///
+    /// This is reset to zero after each rendered frame. You can also reset
+    /// it yourself with [`take_delta`](#method.take_delta).
+    pub delta_x: f64,
+    /// The vertical mouse motion since the last frame was rendered, in
+    /// unspecified device units. Positive values are downwards.
+    pub delta_y: f64,
+    /// The mouse buttons that are currently held down.
+    pub buttons: HashSet<MouseButton>,
+    /// Whether `y` is measured from the top edge of the window instead of the
+    /// bottom edge. Defaults to `false`.
+    pub top_left: bool,
 }
 
 impl MouseState {
@@ -44,26 +105,555 @@ impl MouseState {
         Self {
             x: 0,
             y: 0,
+            x_f32: 0.0,
+            y_f32: 0.0,
             virtual_x: 0,
             virtual_y: 0,
+            delta_x: 0.0,
+            delta_y: 0.0,
+            buttons: HashSet::new(),
+            top_left: false,
         }
     }
 
+    /// Create a MouseState with `y` measured from the top edge of the window,
+    /// like the OS coordinates. For use with the `state` method.
+    pub fn top_left() -> Self {
+        Self {
+            top_left: true,
+            ..Self::new()
+        }
+    }
+
+    /// Whether a mouse button is currently held down.
+    pub fn is_down(&self, button: MouseButton) -> bool {
+        self.buttons.contains(&button)
+    }
+
+    /// Return the relative motion accumulated so far, and reset it to zero.
+    ///
+    /// The motion is already reset after each rendered frame, so this is only
+    /// needed to consume it partway through a frame, like when the motion is
+    /// used in an event handler.
+    /// ```rust,no_run
+    /// # use pixel_canvas::{prelude::*, input::MouseState};
+    /// Canvas::new(512, 512)
+    ///     .grab_cursor(true)
+    ///     .state(MouseState::new())
+    ///     .input(MouseState::handle_input)
+    ///     .render(|mouse, image| {
+    ///         let (dx, dy) = mouse.take_delta();
+    ///         // Turn the camera by (dx, dy)...
+    ///     });
+    /// ```
+    pub fn take_delta(&mut self) -> (f64, f64) {
+        let delta = (self.delta_x, self.delta_y);
+        self.delta_x = 0.0;
+        self.delta_y = 0.0;
+        delta
+    }
+
     /// Handle input for the mouse. For use with the `input` method.
-    pub fn handle_input(info: &CanvasInfo, mouse: &mut MouseState, event: &Event<()>) -> bool {
+    ///
+    /// Returns `true` if the mouse moved or a button changed, so that it
+    /// triggers a redraw with `render_on_change`. This includes moving within
+    /// a pixel, since that changes `x_f32` and `y_f32`.
+    ///
+    /// The OS reports the cursor position in the physical pixels of the
+    /// window, which are converted to virtual pixels with the window's
+    /// `scale_factor`, and then scaled to the pixels of the image, which
+    /// depend on the canvas `dpi`, `render_size` and `supersample`.
+    /// ```rust
+    /// # use pixel_canvas::{canvas::CanvasInfo, input::{MouseState, Event, WindowEvent}};
+    /// # use pixel_canvas::input::glutin::{dpi::PhysicalPosition, event::DeviceId, window::WindowId};
+    /// # #[allow(deprecated)]
+    /// # fn cursor_moved(x: f64, y: f64) -> Event<'static, ()> {
+    /// #     Event::WindowEvent {
+    /// #         window_id: unsafe { WindowId::dummy() },
+    /// #         event: WindowEvent::CursorMoved {
+    /// #             device_id: unsafe { DeviceId::dummy() },
+    /// #             position: PhysicalPosition::new(x, y),
+    /// #             modifiers: Default::default(),
+    /// #         },
+    /// #     }
+    /// # }
+    /// // A 100x50 canvas in hidpi mode on a 2x monitor.
+    /// let mut info = CanvasInfo::new(100, 50);
+    /// info.hidpi = true;
+    /// info.dpi = 2.0;
+    /// info.scale_factor = 2.0;
+    /// let mut mouse = MouseState::new();
+    /// assert!(MouseState::handle_input(&info, &mut mouse, &cursor_moved(20.0, 10.0)));
+    /// assert_eq!((mouse.virtual_x, mouse.virtual_y), (10, 5));
+    /// assert_eq!((mouse.x, mouse.y), (20, 89));
+    /// assert_eq!((mouse.x_f32, mouse.y_f32), (19.5, 89.5));
+    ///
+    /// // Moving within a pixel only changes the precise position.
+    /// assert!(MouseState::handle_input(&info, &mut mouse, &cursor_moved(20.5, 10.0)));
+    /// assert_eq!((mouse.x, mouse.y), (20, 89));
+    /// assert_eq!((mouse.x_f32, mouse.y_f32), (20.0, 89.5));
+    ///
+    /// // Staying in place, or unrelated events, don't change anything.
+    /// assert!(!MouseState::handle_input(&info, &mut mouse, &cursor_moved(20.5, 10.0)));
+    /// assert!(!MouseState::handle_input(&info, &mut mouse, &Event::<()>::MainEventsCleared));
+    ///
+    /// // Without hidpi, the image is in virtual pixels.
+    /// info.hidpi = false;
+    /// info.dpi = 1.0;
+    /// MouseState::handle_input(&info, &mut mouse, &cursor_moved(20.0, 10.0));
+    /// assert_eq!((mouse.x, mouse.y), (10, 44));
+    ///
+    /// // Supersampling makes the image larger, and the positions with it.
+    /// info.supersample = 2;
+    /// MouseState::handle_input(&info, &mut mouse, &cursor_moved(20.0, 10.0));
+    /// assert_eq!((mouse.x, mouse.y), (20, 89));
+    ///
+    /// // With a fixed render size, the positions are scaled to match it.
+    /// info.supersample = 1;
+    /// info.render_size = Some((25, 25));
+    /// MouseState::handle_input(&info, &mut mouse, &cursor_moved(20.0, 10.0));
+    /// assert_eq!((mouse.x, mouse.y), (2, 22));
+    /// assert_eq!((mouse.x_f32, mouse.y_f32), (2.0, 22.0));
+    ///
+    /// // Preserving the aspect ratio centers the 25x25 image in a 200x100
+    /// // window, and the positions over the black bars are outside of it.
+    /// info.preserve_aspect = true;
+    /// MouseState::handle_input(&info, &mut mouse, &cursor_moved(60.0, 10.0));
+    /// assert_eq!((mouse.x, mouse.y), (2, 22));
+    /// MouseState::handle_input(&info, &mut mouse, &cursor_moved(20.0, 10.0));
+    /// assert!(mouse.x < 0);
+    /// MouseState::handle_input(&info, &mut mouse, &cursor_moved(180.0, 10.0));
+    /// assert!(mouse.x >= 25);
+    /// ```
+    pub fn handle_input<T>(info: &CanvasInfo, mouse: &mut MouseState, event: &Event<T>) -> bool {
         match event {
             Event::WindowEvent {
                 event: WindowEvent::CursorMoved { position, .. },
                 ..
             } => {
-                let (x, y): (i32, i32) = (*position).into();
-                mouse.virtual_x = x;
-                mouse.virtual_y = y;
-                mouse.x = (x as f64 * info.dpi) as i32;
-                mouse.y = ((info.height as i32 - y) as f64 * info.dpi) as i32;
+                let old = (mouse.x, mouse.y, mouse.x_f32, mouse.y_f32);
+                let old_virtual = (mouse.virtual_x, mouse.virtual_y);
+                let logical = position.to_logical::<f64>(info.scale_factor);
+                mouse.virtual_x = logical.x as i32;
+                mouse.virtual_y = logical.y as i32;
+                let (x, y) = image_position(info, *position, mouse.top_left);
+                mouse.x = x;
+                mouse.y = y;
+                let (x, y) = precise_image_position(info, *position, mouse.top_left);
+                mouse.x_f32 = x;
+                mouse.y_f32 = y;
+                (mouse.x, mouse.y, mouse.x_f32, mouse.y_f32) != old
+                    || (mouse.virtual_x, mouse.virtual_y) != old_virtual
+            }
+            Event::DeviceEvent {
+                event: DeviceEvent::MouseMotion { delta: (dx, dy) },
+                ..
+            } => {
+                mouse.delta_x += dx;
+                mouse.delta_y += dy;
                 true
             }
+            // Sent by the canvas after each rendered frame.
+            Event::RedrawEventsCleared => {
+                mouse.delta_x = 0.0;
+                mouse.delta_y = 0.0;
+                false
+            }
+            Event::WindowEvent {
+                event: WindowEvent::MouseInput { state, button, .. },
+                ..
+            } => match state {
+                ElementState::Pressed => mouse.buttons.insert(*button),
+                ElementState::Released => mouse.buttons.remove(button),
+            },
+            _ => false,
+        }
+    }
+}
+
+/// Convert a position in the physical pixels of the window to the distance
+/// from the left and top edges of the image the render callback draws into,
+/// measured in its pixels, and return it along with the image's height.
+fn image_coordinates(info: &CanvasInfo, position: PhysicalPosition<f64>) -> (f64, f64, f64) {
+    let (width, height) = info.image_size();
+    let factor = info.supersample.max(1) as f64;
+    let (image_w, image_h) = (width as f64 * factor, height as f64 * factor);
+    let window_w = info.width as f64 * info.scale_factor;
+    let window_h = info.height as f64 * info.scale_factor;
+    // The part of the window the image is drawn into, from the top-left corner.
+    let (left, top, shown_w, shown_h) = if info.preserve_aspect {
+        let (window_w, window_h) = (window_w.round(), window_h.round());
+        let rect = letterbox(
+            (window_w as u32, window_h as u32),
+            (width as u32, height as u32),
+        );
+        let top = window_h - rect.bottom as f64 - rect.height as f64;
+        (rect.left as f64, top, rect.width as f64, rect.height as f64)
+    } else {
+        (0.0, 0.0, window_w, window_h)
+    };
+    let x = (position.x - left) * image_w / shown_w;
+    let from_top = (position.y - top) * image_h / shown_h;
+    (x, from_top, image_h)
+}
+
+/// Convert a position in the physical pixels of the window to the pixels of
+/// the image.
+fn image_position(
+    info: &CanvasInfo,
+    position: PhysicalPosition<f64>,
+    top_left: bool,
+) -> (i32, i32) {
+    let (x, from_top, height) = image_coordinates(info, position);
+    let from_top = from_top.floor() as i32;
+    // Rows of the image are counted from the bottom.
+    let y = if top_left {
+        from_top
+    } else {
+        height as i32 - 1 - from_top
+    };
+    (x.floor() as i32, y)
+}
+
+/// Convert a position in the physical pixels of the window to the pixels of
+/// the image, without rounding, with pixel centers at whole numbers.
+fn precise_image_position(
+    info: &CanvasInfo,
+    position: PhysicalPosition<f64>,
+    top_left: bool,
+) -> (f32, f32) {
+    let (x, from_top, height) = image_coordinates(info, position);
+    let x = x - 0.5;
+    let y = if top_left {
+        from_top - 0.5
+    } else {
+        height - from_top - 0.5
+    };
+    (x as f32, y as f32)
+}
+
+/// A finger touching the screen, tracked by a [`TouchState`].
+///
+/// [`TouchState`]: struct.TouchState.html
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TouchPoint {
+    /// The identifier of the finger, which stays the same while it touches
+    /// the screen.
+    pub id: u64,
+    /// The x position from the left edge, in the pixels of the image.
+    pub x: i32,
+    /// The y position from the bottom edge, in the pixels of the image.
+    pub y: i32,
+}
+
+/// An input handler that tracks the fingers touching a touchscreen.
+///
+/// Touch points use the same coordinates as the physical coordinates of a
+/// [`MouseState`], so they match the pixels of the image.
+/// ```rust,no_run
+/// # use pixel_canvas::{prelude::*, input::TouchState};
+/// Canvas::new(512, 512)
+///     .state(TouchState::new())
+///     .input(TouchState::handle_input)
+///     .render(|touch, image| {
+///         for point in touch.points() {
+///             if let Some(pix) = image.get_mut(XY(point.x as usize, point.y as usize)) {
+///                 *pix = Color::WHITE;
+///             }
+///         }
+///     });
+/// ```
+///
+/// [`MouseState`]: struct.MouseState.html
+pub struct TouchState {
+    points: Vec<TouchPoint>,
+}
+
+impl TouchState {
+    /// Create a TouchState. For use with the `state` method.
+    pub fn new() -> Self {
+        Self { points: Vec::new() }
+    }
+
+    /// The fingers currently touching the screen, in the order they started
+    /// touching it.
+    pub fn points(&self) -> &[TouchPoint] {
+        &self.points
+    }
+
+    /// The finger with an identifier, if it's touching the screen.
+    pub fn get(&self, id: u64) -> Option<&TouchPoint> {
+        self.points.iter().find(|point| point.id == id)
+    }
+
+    /// Handle input for the touchscreen. For use with the `input` method.
+    ///
+    /// Returns `true` if a finger started or stopped touching the screen, or
+    /// moved to a different pixel.
+    /// ```rust
+    /// # use pixel_canvas::{canvas::CanvasInfo, input::{TouchState, TouchPhase}};
+    /// # use pixel_canvas::input::{Event, Touch, WindowEvent};
+    /// # use pixel_canvas::input::glutin::{dpi::PhysicalPosition, event::DeviceId, window::WindowId};
+    /// # #[allow(deprecated)]
+    /// # fn touch_event(id: u64, phase: TouchPhase, x: f64, y: f64) -> Event<'static, ()> {
+    /// #     Event::WindowEvent {
+    /// #         window_id: unsafe { WindowId::dummy() },
+    /// #         event: WindowEvent::Touch(Touch {
+    /// #             device_id: unsafe { DeviceId::dummy() },
+    /// #             phase,
+    /// #             location: PhysicalPosition::new(x, y),
+    /// #             force: None,
+    /// #             id,
+    /// #         }),
+    /// #     }
+    /// # }
+    /// let info = CanvasInfo::new(100, 50);
+    /// let mut touch = TouchState::new();
+    /// TouchState::handle_input(&info, &mut touch, &touch_event(1, TouchPhase::Started, 10.0, 0.0));
+    /// TouchState::handle_input(&info, &mut touch, &touch_event(2, TouchPhase::Started, 20.0, 0.0));
+    /// TouchState::handle_input(&info, &mut touch, &touch_event(1, TouchPhase::Moved, 30.0, 10.0));
+    /// assert_eq!(touch.points().len(), 2);
+    /// assert_eq!((touch.points()[0].x, touch.points()[0].y), (30, 39));
+    /// TouchState::handle_input(&info, &mut touch, &touch_event(2, TouchPhase::Ended, 20.0, 0.0));
+    /// assert_eq!(touch.points().len(), 1);
+    /// assert!(touch.get(2).is_none());
+    /// ```
+    pub fn handle_input<T>(info: &CanvasInfo, touch: &mut TouchState, event: &Event<T>) -> bool {
+        let (id, phase, location) = match event {
+            Event::WindowEvent {
+                event:
+                    WindowEvent::Touch(Touch {
+                        id,
+                        phase,
+                        location,
+                        ..
+                    }),
+                ..
+            } => (*id, *phase, *location),
+            _ => return false,
+        };
+        let index = touch.points.iter().position(|point| point.id == id);
+        match phase {
+            TouchPhase::Started | TouchPhase::Moved => {
+                let (x, y) = image_position(info, location, false);
+                let point = TouchPoint { id, x, y };
+                match index {
+                    Some(i) if touch.points[i] == point => false,
+                    Some(i) => {
+                        touch.points[i] = point;
+                        true
+                    }
+                    None => {
+                        touch.points.push(point);
+                        true
+                    }
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => match index {
+                Some(i) => {
+                    touch.points.remove(i);
+                    true
+                }
+                None => false,
+            },
+        }
+    }
+}
+
+/// An input handler that tracks which keys are held down.
+pub struct KeyboardState {
+    /// The keys that are currently held down.
+    pub pressed: HashSet<VirtualKeyCode>,
+}
+
+impl KeyboardState {
+    /// Create a KeyboardState. For use with the `state` method.
+    pub fn new() -> Self {
+        Self {
+            pressed: HashSet::new(),
+        }
+    }
+
+    /// Whether a key is currently held down.
+    pub fn is_down(&self, key: VirtualKeyCode) -> bool {
+        self.pressed.contains(&key)
+    }
+
+    /// Handle input for the keyboard. For use with the `input` method.
+    ///
+    /// All keys are released when the window loses focus, so that keys
+    /// don't get stuck down if they're released in another window.
+    pub fn handle_input<T>(_info: &CanvasInfo, keys: &mut KeyboardState, event: &Event<T>) -> bool {
+        match event {
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state,
+                                virtual_keycode: Some(key),
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => match state {
+                ElementState::Pressed => keys.pressed.insert(*key),
+                ElementState::Released => keys.pressed.remove(key),
+            },
+            Event::WindowEvent {
+                event: WindowEvent::Focused(false),
+                ..
+            } => {
+                let changed = !keys.pressed.is_empty();
+                keys.pressed.clear();
+                changed
+            }
             _ => false,
         }
     }
 }
+
+/// An input handler that tracks gamepads and controllers.
+///
+/// This requires the `gamepad` feature, which uses the `gilrs` crate.
+/// Gamepads don't send their input through the window, so unlike the other
+/// handlers this isn't used with the `input` method. Instead, pass
+/// [`poll`](#method.poll) to
+/// [`Canvas::on_tick`](../canvas/struct.Canvas.html#method.on_tick), so the
+/// canvas reads the gamepad input every frame, and redraws on changes even
+/// with `render_on_change`. The input of every connected gamepad is combined.
+/// ```rust,no_run
+/// # use pixel_canvas::{prelude::*, input::{Button, GamepadState}};
+/// Canvas::new(512, 512)
+///     .state(GamepadState::new())
+///     .on_tick(GamepadState::poll)
+///     .render(|gamepad, image| {
+///         let (x, y) = gamepad.left_stick();
+///         if gamepad.is_down(Button::South) {
+///             image.fill(Color::WHITE);
+///         }
+///         // Move something by (x, y)...
+///     });
+/// ```
+#[cfg(feature = "gamepad")]
+pub struct GamepadState {
+    gilrs: Option<gilrs::Gilrs>,
+    /// The gamepad buttons that are currently held down.
+    pub buttons: HashSet<Button>,
+    axes: HashMap<Axis, f32>,
+}
+
+#[cfg(feature = "gamepad")]
+impl GamepadState {
+    /// Create a GamepadState. For use with the `state` method.
+    ///
+    /// If gamepads aren't supported on this system, this prints a warning
+    /// and the state never sees any input.
+    pub fn new() -> Self {
+        let gilrs = match gilrs::Gilrs::new() {
+            Ok(gilrs) => Some(gilrs),
+            Err(err) => {
+                eprintln!("pixel-canvas: gamepads aren't available: {}", err);
+                None
+            }
+        };
+        Self {
+            gilrs,
+            buttons: HashSet::new(),
+            axes: HashMap::new(),
+        }
+    }
+
+    /// Read all the gamepad input since the last poll.
+    ///
+    /// Returns `true` if any buttons or axes changed.
+    pub fn poll(&mut self) -> bool {
+        let gilrs = match &mut self.gilrs {
+            Some(gilrs) => gilrs,
+            None => return false,
+        };
+        let mut changed = false;
+        while let Some(event) = gilrs.next_event() {
+            changed |= match event.event {
+                gilrs::EventType::ButtonPressed(button, _) => self.buttons.insert(button),
+                gilrs::EventType::ButtonReleased(button, _) => self.buttons.remove(&button),
+                gilrs::EventType::AxisChanged(axis, value, _) => {
+                    self.axes.insert(axis, value) != Some(value)
+                }
+                gilrs::EventType::Disconnected => {
+                    // Don't leave buttons stuck down or sticks held over.
+                    self.buttons.clear();
+                    self.axes.clear();
+                    true
+                }
+                _ => false,
+            };
+        }
+        changed
+    }
+
+    /// Whether a gamepad button is currently held down.
+    pub fn is_down(&self, button: Button) -> bool {
+        self.buttons.contains(&button)
+    }
+
+    /// The position of an axis, from `-1.0` to `1.0`.
+    pub fn axis(&self, axis: Axis) -> f32 {
+        self.axes.get(&axis).copied().unwrap_or(0.0)
+    }
+
+    /// The position of the left stick, with positive `y` upwards.
+    pub fn left_stick(&self) -> (f32, f32) {
+        (self.axis(Axis::LeftStickX), self.axis(Axis::LeftStickY))
+    }
+
+    /// The position of the right stick, with positive `y` upwards.
+    pub fn right_stick(&self) -> (f32, f32) {
+        (self.axis(Axis::RightStickX), self.axis(Axis::RightStickY))
+    }
+}
+
+/// An input handler that combines a [`MouseState`] and a [`KeyboardState`].
+///
+/// Since a canvas only has one state, use this when you want to track both
+/// the mouse and the keyboard.
+/// ```rust,no_run
+/// # use pixel_canvas::{prelude::*, input::{InputState, VirtualKeyCode}};
+/// Canvas::new(512, 512)
+///     .state(InputState::new())
+///     .input(InputState::handle_input)
+///     .render(|input, image| {
+///         if input.keys.is_down(VirtualKeyCode::Space) {
+///             image.fill(Color::WHITE);
+///         }
+///         let (x, y) = (input.mouse.x, input.mouse.y);
+///         // ...
+///     });
+/// ```
+///
+/// [`MouseState`]: struct.MouseState.html
+/// [`KeyboardState`]: struct.KeyboardState.html
+pub struct InputState {
+    /// The state of the mouse.
+    pub mouse: MouseState,
+    /// The state of the keyboard.
+    pub keys: KeyboardState,
+}
+
+impl InputState {
+    /// Create an InputState. For use with the `state` method.
+    pub fn new() -> Self {
+        Self {
+            mouse: MouseState::new(),
+            keys: KeyboardState::new(),
+        }
+    }
+
+    /// Handle input for the mouse and keyboard. For use with the `input`
+    /// method.
+    pub fn handle_input<T>(info: &CanvasInfo, input: &mut InputState, event: &Event<T>) -> bool {
+        MouseState::handle_input(info, &mut input.mouse, event)
+            | KeyboardState::handle_input(info, &mut input.keys, event)
+    }
+}
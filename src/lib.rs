@@ -31,8 +31,7 @@
 //!     // The canvas will render for you at up to 60fps.
 //!     canvas.render(|mouse, image| {
 //!         // Modify the `image` based on your state.
-//!         let width = image.width() as usize;
-//!         for (y, row) in image.chunks_mut(width).enumerate() {
+//!         for (y, row) in image.rows_mut_enumerated() {
 //!             for (x, pixel) in row.iter_mut().enumerate() {
 //!                 let dx = x as i32 - mouse.x;
 //!                 let dy = y as i32 - mouse.y;
@@ -47,9 +46,20 @@
 //!     });
 //! }
 //! ```
+//!
+//! # Features
+//!
+//! - `rayon`: Fill images and render fractals in parallel, and iterate over
+//!   pixels in parallel with `Image::par_enumerate_pixels_mut`.
+//! - `simd`: Enable the `packed_simd` dependency for SIMD examples like
+//!   `julia-simd`. This requires a nightly compiler.
+//! - `gamepad`: Read gamepads and controllers with `input::GamepadState`,
+//!   using the `gilrs` crate.
 
 pub mod canvas;
 pub mod color;
+mod font;
+pub mod fractal;
 pub mod image;
 pub mod input;
 pub mod math;
@@ -58,3 +68,7 @@ pub mod vector;
 
 #[doc(inline)]
 pub use prelude::*;
+
+/// The version of `glium` used by the canvas, for drawing your own OpenGL with
+/// [`Canvas::render_custom`](struct.Canvas.html#method.render_custom).
+pub use glium;
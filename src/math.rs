@@ -1,5 +1,5 @@
 //! Useful common math operations for doing art.
-use std::ops::{Add, Div, Mul, Range, RangeFrom, RangeInclusive, RangeToInclusive, Sub};
+use std::ops::{Add, Div, Mul, Range, RangeFrom, RangeInclusive, RangeTo, RangeToInclusive, Sub};
 
 /// Represent types that can be restricted by a given range type.
 ///
@@ -10,8 +10,15 @@ pub trait Restrict<RangeType> {
     ///
     /// If a value is below the minimum bound, it should be clamped to that
     /// value, and if it's above its max value it should be clamped to that.
-    /// This is only provided for inclusive ranges, since the behavior for
-    /// exclusive ranges of some types are less immediately clear.
+    /// Exclusive ranges are only provided for integers, where the max value
+    /// is `end - 1`, which makes them useful for clamping indices.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// assert_eq!(1.5.restrict(0.0..=1.0), 1.0);
+    /// assert_eq!((-3).restrict(0..10), 0);
+    /// assert_eq!(12.restrict(0..10), 9);
+    /// assert_eq!(12usize.restrict(..10), 9);
+    /// ```
     fn restrict(self, range: RangeType) -> Self;
 }
 
@@ -55,6 +62,37 @@ where
     }
 }
 
+macro_rules! impl_restrict_exclusive {
+    ($($t:ty),*) => {$(
+        impl Restrict<Range<$t>> for $t {
+            /// Panics if the range is empty.
+            fn restrict(self, range: Range<$t>) -> $t {
+                assert!(range.start < range.end, "cannot restrict into an empty range");
+                if self >= range.end {
+                    return range.end - 1;
+                }
+                if self < range.start {
+                    return range.start;
+                }
+                self
+            }
+        }
+
+        impl Restrict<RangeTo<$t>> for $t {
+            /// Panics if the range is empty.
+            fn restrict(self, range: RangeTo<$t>) -> $t {
+                assert!(<$t>::MIN < range.end, "cannot restrict into an empty range");
+                if self >= range.end {
+                    return range.end - 1;
+                }
+                self
+            }
+        }
+    )*};
+}
+
+impl_restrict_exclusive!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
 /// Represents a type that can be mapped between two ranges.
 pub trait Remap
 where
@@ -68,6 +106,19 @@ where
     /// assert_eq!(0.5.remap(0.0..1.0, -1.0..1.0), 0.0);
     /// ```
     fn remap(self, from: Range<Self>, onto: Range<Self>) -> Self;
+
+    /// Remap a value from one range to another, first restricting it to the
+    /// bounds of the source range. The result always lies within the bounds
+    /// of the destination range.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// assert_eq!(5.remap_clamped(-10..10, -100..100), 50);
+    /// assert_eq!(20.remap_clamped(-10..10, -100..100), 100);
+    /// assert_eq!((-1.0).remap_clamped(0.0..1.0, 1.0..0.0), 1.0);
+    /// ```
+    fn remap_clamped(self, from: Range<Self>, onto: Range<Self>) -> Self
+    where
+        Self: PartialOrd;
 }
 
 impl<T> Remap for T
@@ -79,4 +130,200 @@ where
         let onto_size = onto.end - onto.start;
         ((self - from.start) * onto_size / from_size) + onto.start
     }
+
+    fn remap_clamped(self, from: Range<Self>, onto: Range<Self>) -> Self
+    where
+        Self: PartialOrd,
+    {
+        let clamped = if from.start <= from.end {
+            self.restrict(from.start..=from.end)
+        } else {
+            self.restrict(from.end..=from.start)
+        };
+        clamped.remap(from, onto)
+    }
+}
+
+/// Linearly interpolate between two values, like GLSL's `mix`.
+///
+/// A factor of `0.0` gives `a`, and `1.0` gives `b`. The factor isn't
+/// clamped, so factors outside of `0.0..=1.0` extrapolate.
+/// ```rust
+/// # use pixel_canvas::{prelude::*, math::lerp};
+/// assert_eq!(lerp(10.0, 20.0, 0.25), 12.5);
+/// assert_eq!(lerp(10.0, 20.0, 2.0), 30.0);
+/// let v = lerp(Vec3::ZERO, Vec3::xyz(2.0, 4.0, 8.0), 0.5);
+/// assert_eq!(v, Vec3::xyz(1.0, 2.0, 4.0));
+/// ```
+pub fn lerp<T>(a: T, b: T, t: f32) -> T
+where
+    T: Add<Output = T> + Mul<f32, Output = T>,
+{
+    a * (1.0 - t) + b * t
+}
+
+/// Smooth Hermite interpolation between 0 and 1, like GLSL's `smoothstep`.
+///
+/// The result is `0.0` when `x <= edge0`, `1.0` when `x >= edge1`, and
+/// follows the curve `3t² - 2t³` in between.
+/// ```rust
+/// # use pixel_canvas::math::smoothstep;
+/// assert_eq!(smoothstep(0.0, 1.0, -1.0), 0.0);
+/// assert_eq!(smoothstep(0.0, 1.0, 0.5), 0.5);
+/// assert_eq!(smoothstep(0.0, 2.0, 0.5), 0.15625);
+/// assert_eq!(smoothstep(0.0, 1.0, 2.0), 1.0);
+/// ```
+pub fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).restrict(0.0..=1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// A seeded generator for 2D value noise, with optional fractal layering.
+///
+/// Value noise assigns a pseudo-random value to each point on an integer
+/// lattice, and smoothly interpolates between them. Layering several octaves
+/// of noise at increasing frequencies and decreasing amplitudes (fractal
+/// Brownian motion, or fBm) gives more natural looking detail.
+///
+/// The same seed and settings always produce the same noise, so your art is
+/// reproducible.
+/// ```rust
+/// # use pixel_canvas::math::Noise;
+/// let noise = Noise::new(42).octaves(4);
+/// let value = noise.sample(1.5, 2.25);
+/// assert!(-1.0 <= value && value <= 1.0);
+/// assert_eq!(value, Noise::new(42).octaves(4).sample(1.5, 2.25));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Noise {
+    seed: u32,
+    octaves: u32,
+    lacunarity: f32,
+    gain: f32,
+}
+
+impl Noise {
+    /// Create a single octave noise generator with the given seed.
+    pub fn new(seed: u32) -> Noise {
+        Noise {
+            seed,
+            octaves: 1,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+
+    /// Set the number of octaves of noise that are layered together.
+    ///
+    /// Defaults to `1`, which is plain value noise.
+    pub fn octaves(self, octaves: u32) -> Noise {
+        Noise {
+            octaves: octaves.max(1),
+            ..self
+        }
+    }
+
+    /// Set the factor the frequency is multiplied by for each octave.
+    ///
+    /// Defaults to `2.0`.
+    pub fn lacunarity(self, lacunarity: f32) -> Noise {
+        Noise { lacunarity, ..self }
+    }
+
+    /// Set the factor the amplitude is multiplied by for each octave.
+    ///
+    /// Defaults to `0.5`.
+    pub fn gain(self, gain: f32) -> Noise {
+        Noise { gain, ..self }
+    }
+
+    /// Sample the noise at a point, giving a value in the range `-1.0..=1.0`.
+    ///
+    /// The lattice points are at integer coordinates, so features of the
+    /// first octave are about one unit across.
+    pub fn sample(&self, x: f32, y: f32) -> f32 {
+        let mut total = 0.0;
+        let mut norm = 0.0;
+        let mut frequency = 1.0;
+        let mut amplitude = 1.0;
+        for octave in 0..self.octaves {
+            let seed = self.seed.wrapping_add(octave);
+            total += value_noise(seed, x * frequency, y * frequency) * amplitude;
+            norm += amplitude;
+            frequency *= self.lacunarity;
+            amplitude *= self.gain;
+        }
+        total / norm
+    }
+}
+
+/// A small, fast, seeded pseudo-random number generator.
+///
+/// This uses the SplitMix64 algorithm. It's not cryptographically secure, but
+/// it's fast and has good statistical quality, which is what you want for
+/// art. The same seed always produces the same sequence of numbers, on every
+/// platform.
+/// ```rust
+/// # use pixel_canvas::math::Rng;
+/// let mut rng = Rng::new(42);
+/// let x = rng.next_f32();
+/// assert!(0.0 <= x && x < 1.0);
+/// assert_eq!(x, Rng::new(42).next_f32());
+/// let y = rng.range(-1.0..1.0);
+/// assert!(-1.0 <= y && y < 1.0);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Create a random number generator from a seed.
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    /// The next random `u64`.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// The next random `u32`.
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// A random `f32` in `0.0..1.0`.
+    pub fn next_f32(&mut self) -> f32 {
+        // Use the top 24 bits, which is all the precision an f32 has.
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// A random `f32` in a range.
+    pub fn range(&mut self, range: Range<f32>) -> f32 {
+        self.next_f32().remap(0.0..1.0, range)
+    }
+}
+
+fn value_noise(seed: u32, x: f32, y: f32) -> f32 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (ix, iy) = (x0 as i32, y0 as i32);
+    let fade = |t: f32| t * t * (3.0 - 2.0 * t);
+    let (tx, ty) = (fade(x - x0), fade(y - y0));
+    let (ix1, iy1) = (ix.wrapping_add(1), iy.wrapping_add(1));
+    let top = lerp(lattice(seed, ix, iy), lattice(seed, ix1, iy), tx);
+    let bottom = lerp(lattice(seed, ix, iy1), lattice(seed, ix1, iy1), tx);
+    lerp(top, bottom, ty)
+}
+
+fn lattice(seed: u32, x: i32, y: i32) -> f32 {
+    let mut h = seed ^ (x as u32).wrapping_mul(0x27d4_eb2d) ^ (y as u32).wrapping_mul(0x1656_67b1);
+    h = (h ^ (h >> 15)).wrapping_mul(0x2c1b_3c6d);
+    h = (h ^ (h >> 12)).wrapping_mul(0x297a_2d39);
+    h ^= h >> 15;
+    h as f32 / u32::MAX as f32 * 2.0 - 1.0
 }
@@ -1,9 +1,10 @@
 //! Re-exported types and traits, meant to be glob imported for convenience.
 
 pub use crate::{
-    canvas::Canvas,
+    canvas::{Canvas, HandlerResponse, ScalingFilter},
     color::{Blend, Color},
     image::{Image, RC, XY},
+    input::{Event, MouseState, WindowEvent},
     math::{Remap, Restrict},
-    vector::Vec3,
+    vector::{Vec2, Vec3},
 };
@@ -1,9 +1,118 @@
 //! Types and operations for vectors.
 
-use std::ops::{Add, Div, Mul, Sub};
+use std::ops::{Add, Div, Index, IndexMut, Mul, Sub};
+
+/// A 2-dimensional vector.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[allow(missing_docs)]
+pub struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Vec2 {
+    /// Construct a vector out of its components.
+    pub fn xy(x: f32, y: f32) -> Self {
+        Vec2 { x, y }
+    }
+
+    /// Construct a vector with all components set to the same value.
+    pub fn splat(v: f32) -> Self {
+        Vec2 { x: v, y: v }
+    }
+
+    /// Normalizes the vector (scales its length to 1).
+    pub fn normal(self) -> Self {
+        self / self.len()
+    }
+
+    /// Computes the dot product between two vectors.
+    pub fn dot(self, rhs: Vec2) -> f32 {
+        self.x * rhs.x + self.y * rhs.y
+    }
+
+    /// The length of a vector.
+    pub fn len(&self) -> f32 {
+        self.len2().sqrt()
+    }
+
+    /// The squared length of a vector.
+    pub fn len2(&self) -> f32 {
+        self.x * self.x + self.y * self.y
+    }
+
+    /// The vector with its components swapped.
+    pub fn yx(self) -> Vec2 {
+        Vec2::xy(self.y, self.x)
+    }
+}
+
+impl Add<Vec2> for Vec2 {
+    type Output = Vec2;
+    fn add(self, rhs: Vec2) -> Self {
+        Vec2 {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+        }
+    }
+}
+
+impl Sub<Vec2> for Vec2 {
+    type Output = Vec2;
+    fn sub(self, rhs: Vec2) -> Self {
+        Vec2 {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+        }
+    }
+}
+
+impl Mul<f32> for Vec2 {
+    type Output = Vec2;
+    fn mul(self, rhs: f32) -> Self {
+        Vec2 {
+            x: self.x * rhs,
+            y: self.y * rhs,
+        }
+    }
+}
+
+impl Div<f32> for Vec2 {
+    type Output = Vec2;
+    fn div(self, rhs: f32) -> Self {
+        Vec2 {
+            x: self.x / rhs,
+            y: self.y / rhs,
+        }
+    }
+}
+
+/// Access the components by index, `0` is `x` and `1` is `y`.
+///
+/// Panics if the index is larger than `1`.
+impl Index<usize> for Vec2 {
+    type Output = f32;
+    fn index(&self, index: usize) -> &f32 {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            _ => panic!("index {} is out of range for Vec2", index),
+        }
+    }
+}
+
+impl IndexMut<usize> for Vec2 {
+    fn index_mut(&mut self, index: usize) -> &mut f32 {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            _ => panic!("index {} is out of range for Vec2", index),
+        }
+    }
+}
 
 /// A 3-dimensional vector.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 #[allow(missing_docs)]
 pub struct Vec3 {
     pub x: f32,
@@ -12,11 +121,57 @@ pub struct Vec3 {
 }
 
 impl Vec3 {
+    /// The vector with all components set to zero.
+    pub const ZERO: Vec3 = Vec3 {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    };
+    /// The vector with all components set to one.
+    pub const ONE: Vec3 = Vec3 {
+        x: 1.0,
+        y: 1.0,
+        z: 1.0,
+    };
+
     /// Construct a vector out of its components.
     pub fn xyz(x: f32, y: f32, z: f32) -> Self {
         Vec3 { x, y, z }
     }
 
+    /// Construct a vector with all components set to the same value.
+    pub fn splat(v: f32) -> Self {
+        Vec3 { x: v, y: v, z: v }
+    }
+
+    /// The component-wise minimum of two vectors.
+    pub fn min(self, rhs: Vec3) -> Vec3 {
+        Vec3 {
+            x: self.x.min(rhs.x),
+            y: self.y.min(rhs.y),
+            z: self.z.min(rhs.z),
+        }
+    }
+
+    /// The component-wise maximum of two vectors.
+    pub fn max(self, rhs: Vec3) -> Vec3 {
+        Vec3 {
+            x: self.x.max(rhs.x),
+            y: self.y.max(rhs.y),
+            z: self.z.max(rhs.z),
+        }
+    }
+
+    /// Clamps each component between the matching components of `lo` and `hi`.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let v = Vec3::xyz(-1.0, 0.5, 2.0).clamp(Vec3::ZERO, Vec3::ONE);
+    /// assert_eq!(v, Vec3::xyz(0.0, 0.5, 1.0));
+    /// ```
+    pub fn clamp(self, lo: Vec3, hi: Vec3) -> Vec3 {
+        self.max(lo).min(hi)
+    }
+
     /// Normalizes the vector (scales its length to 1).
     pub fn normal(self) -> Self {
         self / self.len()
@@ -45,6 +200,32 @@ impl Vec3 {
     pub fn len2(&self) -> f32 {
         self.x * self.x + self.y * self.y + self.z * self.z
     }
+
+    /// The `x` and `y` components, like `v.xy` in a shader.
+    /// ```rust
+    /// # use pixel_canvas::prelude::*;
+    /// let v = Vec3::xyz(1.0, 2.0, 3.0);
+    /// assert_eq!(v.xy(), Vec2::xy(1.0, 2.0));
+    /// assert_eq!(v.zyx(), Vec3::xyz(3.0, 2.0, 1.0));
+    /// ```
+    pub fn xy(self) -> Vec2 {
+        Vec2::xy(self.x, self.y)
+    }
+
+    /// The `x` and `z` components.
+    pub fn xz(self) -> Vec2 {
+        Vec2::xy(self.x, self.z)
+    }
+
+    /// The `y` and `z` components.
+    pub fn yz(self) -> Vec2 {
+        Vec2::xy(self.y, self.z)
+    }
+
+    /// The vector with its components reversed.
+    pub fn zyx(self) -> Vec3 {
+        Vec3::xyz(self.z, self.y, self.x)
+    }
 }
 
 impl Add<Vec3> for Vec3 {
@@ -80,6 +261,29 @@ impl Mul<f32> for Vec3 {
     }
 }
 
+/// Component-wise (Hadamard) product.
+///
+/// Each component is multiplied by the matching component, this is neither
+/// the [`dot`] nor the [`cross`] product.
+/// ```rust
+/// # use pixel_canvas::prelude::*;
+/// let v = Vec3::xyz(1.0, 2.0, 3.0) * Vec3::xyz(4.0, 5.0, 6.0);
+/// assert_eq!(v, Vec3::xyz(4.0, 10.0, 18.0));
+/// ```
+///
+/// [`dot`]: struct.Vec3.html#method.dot
+/// [`cross`]: struct.Vec3.html#method.cross
+impl Mul<Vec3> for Vec3 {
+    type Output = Vec3;
+    fn mul(self, rhs: Vec3) -> Self {
+        Vec3 {
+            x: self.x * rhs.x,
+            y: self.y * rhs.y,
+            z: self.z * rhs.z,
+        }
+    }
+}
+
 impl Div<f32> for Vec3 {
     type Output = Vec3;
     fn div(self, rhs: f32) -> Self {
@@ -90,3 +294,51 @@ impl Div<f32> for Vec3 {
         }
     }
 }
+
+/// Access the components by index, `0` is `x`, `1` is `y`, and `2` is `z`.
+///
+/// This makes it easy to loop over the components.
+/// ```rust
+/// # use pixel_canvas::prelude::*;
+/// let mut v = Vec3::xyz(1.0, 2.0, 3.0);
+/// for i in 0..3 {
+///     v[i] *= 2.0;
+/// }
+/// assert_eq!(v[2], 6.0);
+/// ```
+///
+/// Panics if the index is larger than `2`.
+impl Index<usize> for Vec3 {
+    type Output = f32;
+    fn index(&self, index: usize) -> &f32 {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("index {} is out of range for Vec3", index),
+        }
+    }
+}
+
+impl IndexMut<usize> for Vec3 {
+    fn index_mut(&mut self, index: usize) -> &mut f32 {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            2 => &mut self.z,
+            _ => panic!("index {} is out of range for Vec3", index),
+        }
+    }
+}
+
+impl From<[f32; 3]> for Vec3 {
+    fn from([x, y, z]: [f32; 3]) -> Vec3 {
+        Vec3 { x, y, z }
+    }
+}
+
+impl From<Vec3> for [f32; 3] {
+    fn from(v: Vec3) -> [f32; 3] {
+        [v.x, v.y, v.z]
+    }
+}